@@ -1,14 +1,244 @@
-use std::str::FromStr;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, Parser};
+
+use crate::{
+    days::{day16, day17},
+    output::{Column, Palette, Style, TableOptions},
+    runner::{self, DayTimings},
+    visualize,
+};
 
 /// Run Advent of Code 2022 solutions with testing and timing.
 #[derive(Parser, Debug)]
-#[command(author, version)]
+#[command(author, version, args_conflicts_with_subcommands = true)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Args {
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
 
+    #[command(subcommand)]
+    command: Option<Subcommand>,
+
+    #[command(flatten)]
+    selection: Selection,
+
+    /// Show a live dashboard which fills in timings as they are measured.
+    ///
+    /// Use the arrow keys to select a day or part, `r` or enter to re-run the
+    /// selection, and `q` to quit.
+    #[arg(long, conflicts_with = "json")]
+    tui: bool,
+
+    /// Print the timings as JSON instead of a table.
+    #[arg(long)]
+    json: bool,
+
+    /// Also write an HTML report and an SVG chart of the timings to this directory.
+    #[arg(long, value_name = "DIR")]
+    report: Option<PathBuf>,
+
+    /// Show the simulation of each selected day which has a visualisation,
+    /// instead of timing it.
+    #[arg(long, conflicts_with_all = ["tui", "json", "report", "variants"])]
+    visualize: bool,
+
+    /// Print the best plan found by each selected part which can explain
+    /// itself, minute by minute, instead of timing it.
+    #[arg(long, conflicts_with_all = ["tui", "json", "report", "visualize", "variants"])]
+    explain: bool,
+
+    /// Save visualisations to files in this directory instead of animating
+    /// them in the terminal.
+    #[arg(long, value_name = "DIR", requires = "visualize")]
+    frames: Option<PathBuf>,
+
+    /// The format to save visualisations in.
+    #[arg(long, value_enum, default_value_t = visualize::Format::Gif)]
+    frame_format: visualize::Format,
+
+    /// The time to show each frame of a visualisation for, in milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 50)]
+    frame_delay: u64,
+
+    #[command(flatten)]
+    variants: Variants,
+
+    #[command(flatten)]
+    display: Display,
+}
+
+#[derive(clap::Args, Debug)]
+struct Variants {
+    /// Run an alternative solution for a picked day. Repeat for more days.
+    ///
+    /// `dense` stores day 17's trench as a bitmask per row, and `bitwise`
+    /// simulates day 23 a whole row of elves at a time.
+    #[arg(long = "variant", value_name = "NAME", value_parser = variant_names())]
+    variants: Vec<String>,
+}
+
+fn variant_names() -> clap::builder::PossibleValuesParser {
+    clap::builder::PossibleValuesParser::new(runner::VARIANTS.map(|(name, _)| name))
+}
+
+impl Variants {
+    /// The chosen variants, exiting with an error if one is for a day which
+    /// isn't picked.
+    fn for_picks(self, picks: &[DayPick; 25]) -> Vec<String> {
+        for name in &self.variants {
+            let (_, day) = runner::VARIANTS
+                .into_iter()
+                .find(|(variant, _)| variant == name)
+                .unwrap();
+            let pick = picks[day - 1];
+            if !(pick.parse || pick.part1 || pick.part2) {
+                Args::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        format!("variant {name} is for day {day}, which isn't picked"),
+                    )
+                    .exit();
+            }
+        }
+        self.variants
+    }
+}
+
+#[derive(clap::Args, Debug)]
+struct Display {
+    /// When to use colour.
+    ///
+    /// `auto` uses colour only when writing to a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    #[arg(long, value_enum, default_value_t = ColourChoice::Auto, global = true)]
+    colour: ColourChoice,
+
+    /// Colours to use for timings.
+    #[arg(long, value_enum, default_value_t = Palette::Traffic, global = true)]
+    palette: Palette,
+
+    /// Mark slow parts and wrong answers with symbols as well as colour.
+    ///
+    /// This is always done when colour is disabled.
+    #[arg(long, global = true)]
+    symbols: bool,
+
+    /// Columns to show in the timing table, separated by commas.
+    ///
+    /// Defaults to day, parse, part1, part2 and total, plus speedup if a
+    /// baseline is given.
+    #[arg(long, value_enum, value_delimiter = ',', global = true)]
+    columns: Option<Vec<Column>>,
+
+    /// Timings to compare against in the speedup column, as written by `--json`.
+    #[arg(long, value_name = "FILE", global = true)]
+    baseline: Option<PathBuf>,
+}
+
+impl Display {
+    fn table_options(&self) -> TableOptions {
+        let baseline = self
+            .baseline
+            .as_deref()
+            .map(load_baseline)
+            .unwrap_or_default();
+        let columns = self.columns.clone().unwrap_or_else(|| {
+            let mut columns = Column::DEFAULT.to_vec();
+            if self.baseline.is_some() {
+                columns.push(Column::Speedup);
+            }
+            columns
+        });
+        TableOptions { columns, baseline }
+    }
+}
+
+fn load_baseline(path: &Path) -> Vec<DayTimings> {
+    let timings = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()));
+    timings.unwrap_or_else(|err| {
+        Args::command()
+            .error(
+                ErrorKind::ValueValidation,
+                format!("could not read baseline {}: {err}", path.display()),
+            )
+            .exit()
+    })
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum ColourChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Subcommand {
+    /// Watch the solutions and inputs, rebuilding and re-running days as they change.
+    ///
+    /// Only the selected days are watched. Each run prints the new timings along with
+    /// how they changed since the previous run.
+    Watch {
+        #[command(flatten)]
+        selection: Selection,
+
+        #[command(flatten)]
+        variants: Variants,
+    },
+
+    /// Run day 16's valve optimizer on a custom scenario and print the best plan.
+    Valves {
+        /// The valves and tunnels, in the puzzle's format. Defaults to day 16's input.
+        #[arg(long, value_name = "FILE")]
+        input: Option<PathBuf>,
+
+        /// An agent's starting valve and minutes available. Repeat for more agents.
+        ///
+        /// Every agent's minutes count down to the same end, so an agent with fewer
+        /// minutes starts later.
+        #[arg(long = "agent", value_name = "VALVE:MINUTES", default_value = "AA:30")]
+        agents: Vec<day16::Agent>,
+
+        /// The minutes it takes to open a valve.
+        #[arg(long, value_name = "MINUTES", default_value_t = 1)]
+        opening_cost: usize,
+
+        /// How to share the valves between agents.
+        #[arg(long, value_enum, default_value_t = day16::Method::Search)]
+        method: day16::Method,
+    },
+
+    /// Run day 17's falling-block simulator with custom jets, blocks or trench
+    /// width, and print the height of the tower.
+    Blocks {
+        /// The jets, in the puzzle's format. Defaults to day 17's input.
+        #[arg(long, value_name = "FILE")]
+        input: Option<PathBuf>,
+
+        /// The blocks to drop in turn, drawn with `#` and `.` and separated by
+        /// blank lines. Defaults to the puzzle's five blocks.
+        #[arg(long, value_name = "FILE")]
+        blocks: Option<PathBuf>,
+
+        /// The width of the trench.
+        #[arg(long, default_value_t = day17::WIDTH as usize)]
+        width: usize,
+
+        /// The number of blocks to drop.
+        #[arg(long, default_value_t = 2022)]
+        count: usize,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct Selection {
     /// Which parts to run.
     ///
     /// Examples:
@@ -23,13 +253,131 @@ pub struct Args {
     parts: Vec<PartSelection>,
 }
 
+pub struct Options {
+    pub picks: [DayPick; 25],
+    pub style: Style,
+    pub table: TableOptions,
+    pub command: Command,
+}
+
+pub enum Command {
+    Run {
+        tui: bool,
+        json: bool,
+        report: Option<PathBuf>,
+        variants: Vec<String>,
+    },
+    Visualize(visualize::Options),
+    Explain,
+    Valves {
+        input: Option<PathBuf>,
+        scenario: day16::Scenario,
+        method: day16::Method,
+    },
+    Blocks {
+        input: Option<PathBuf>,
+        blocks: Option<PathBuf>,
+        width: usize,
+        count: usize,
+    },
+    Watch {
+        variants: Vec<String>,
+    },
+}
+
 impl Args {
-    pub fn get_picks() -> [DayPick; 25] {
+    pub fn get_options() -> Options {
         let args = Self::parse();
         pretty_env_logger::formatted_builder()
             .filter_level(args.verbose.log_level_filter())
             .init();
-        normalise_parts(&args.parts)
+        let force_colour = match args.display.colour {
+            ColourChoice::Auto => None,
+            ColourChoice::Always => Some(true),
+            ColourChoice::Never => Some(false),
+        };
+        let style = Style::detect(force_colour, args.display.palette, args.display.symbols);
+        let table = args.display.table_options();
+        match args.command {
+            None if args.visualize => Options {
+                picks: normalise_parts(&args.selection.parts),
+                style,
+                table,
+                command: Command::Visualize(visualize::Options {
+                    dir: args.frames,
+                    format: args.frame_format,
+                    delay: Duration::from_millis(args.frame_delay),
+                }),
+            },
+            None if args.explain => Options {
+                picks: normalise_parts(&args.selection.parts),
+                style,
+                table,
+                command: Command::Explain,
+            },
+            None => {
+                let picks = normalise_parts(&args.selection.parts);
+                Options {
+                    picks,
+                    style,
+                    table,
+                    command: Command::Run {
+                        tui: args.tui,
+                        json: args.json,
+                        report: args.report,
+                        variants: args.variants.for_picks(&picks),
+                    },
+                }
+            }
+            Some(Subcommand::Watch {
+                selection,
+                variants,
+            }) => {
+                let picks = normalise_parts(&selection.parts);
+                Options {
+                    picks,
+                    style,
+                    table,
+                    command: Command::Watch {
+                        variants: variants.for_picks(&picks),
+                    },
+                }
+            }
+            Some(Subcommand::Valves {
+                input,
+                agents,
+                opening_cost,
+                method,
+            }) => Options {
+                picks: normalise_parts(&[]),
+                style,
+                table,
+                command: Command::Valves {
+                    input,
+                    scenario: day16::Scenario {
+                        agents,
+                        opening_cost,
+                    },
+                    method,
+                },
+            },
+            Some(Subcommand::Blocks {
+                input,
+                blocks,
+                width,
+                count,
+            }) => Options {
+                picks: normalise_parts(&[]),
+                style,
+                table,
+                command: Command::Blocks {
+                    input,
+                    blocks,
+                    width,
+                    count,
+                },
+            },
+        }
     }
 }
 
@@ -49,18 +397,24 @@ impl FromStr for PartSelection {
         if s == "all" {
             Ok(Self::All)
         } else if let Some(day) = s.strip_suffix('a') {
-            day.parse().map(PartSelection::Part1).map_err(|_| "invalid day")
+            day.parse()
+                .map(PartSelection::Part1)
+                .map_err(|_| "invalid day")
         } else if let Some(day) = s.strip_suffix('b') {
-            day.parse().map(PartSelection::Part2).map_err(|_| "invalid day")
+            day.parse()
+                .map(PartSelection::Part2)
+                .map_err(|_| "invalid day")
         } else if let Some(day) = s.strip_suffix('p') {
-            day.parse().map(PartSelection::Parse).map_err(|_| "invalid day")
+            day.parse()
+                .map(PartSelection::Parse)
+                .map_err(|_| "invalid day")
         } else {
             s.parse().map(PartSelection::Day).map_err(|_| "invalid day")
         }
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub struct DayPick {
     pub day: usize,
     pub parse: bool,
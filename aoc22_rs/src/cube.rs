@@ -0,0 +1,501 @@
+//! Cube nets: six squares on a grid which fold up into a cube.
+
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use crate::{grid::Grid, vec2::Vec2, vec2::Vec3};
+
+/// An edge of a face, as seen on the net. Listed clockwise, so turning
+/// clockwise moves to the next edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Edge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+impl Edge {
+    pub const ALL: [Self; 4] = [Self::Left, Self::Top, Self::Right, Self::Bottom];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The direction out of a face across this edge, on the net.
+    pub const fn direction(self) -> Vec2 {
+        match self {
+            Self::Left => Vec2::new(-1, 0),
+            Self::Top => Vec2::new(0, -1),
+            Self::Right => Vec2::new(1, 0),
+            Self::Bottom => Vec2::new(0, 1),
+        }
+    }
+}
+
+/// Where leaving a face across one of its edges leads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Join {
+    /// The index of the face on the other side.
+    pub face: usize,
+    /// The edge of that face which is crossed.
+    pub edge: Edge,
+    /// The clockwise quarter turns which map a direction on the first face's
+    /// part of the net to the same direction on the other face's.
+    pub quarter_turns: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Face {
+    /// Position on the net in faces, so `position * face_size` is the top left
+    /// corner of the face in the grid.
+    pub position: Vec2,
+    /// The join across each edge, indexed by [`Edge::index`].
+    pub joins: [Join; 4],
+}
+
+impl Face {
+    pub const fn join(&self, edge: Edge) -> Join {
+        self.joins[edge.index()]
+    }
+}
+
+/// The four kinds of cube net, named by the lengths of their rows when the
+/// longest row is horizontal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    OneFourOne,
+    TwoThreeOne,
+    TwoTwoTwo,
+    ThreeThree,
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OneFourOne => "1-4-1",
+            Self::TwoThreeOne => "2-3-1",
+            Self::TwoTwoTwo => "2-2-2",
+            Self::ThreeThree => "3-3",
+        })
+    }
+}
+
+/// Which of the 11 cube nets a net is, ignoring rotations and reflections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shape {
+    /// From 1 to 11, in the order of [`SHAPES`].
+    pub number: usize,
+    pub family: Family,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "net {} ({})", self.number, self.family)
+    }
+}
+
+/// One of each of the 11 cube nets, with `#` for faces.
+const SHAPES: [(Family, &str); 11] = [
+    (Family::OneFourOne, "#...\n####\n#..."),
+    (Family::OneFourOne, "#...\n####\n.#.."),
+    (Family::OneFourOne, "#...\n####\n..#."),
+    (Family::OneFourOne, "#...\n####\n...#"),
+    (Family::OneFourOne, ".#..\n####\n.#.."),
+    (Family::OneFourOne, ".#..\n####\n..#."),
+    (Family::TwoThreeOne, "##..\n.###\n.#.."),
+    (Family::TwoThreeOne, "##..\n.###\n..#."),
+    (Family::TwoThreeOne, "##..\n.###\n...#"),
+    (Family::TwoTwoTwo, "##..\n.##.\n..##"),
+    (Family::ThreeThree, "###..\n..###"),
+];
+
+/// Why a grid isn't a cube net.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetError {
+    /// The grid isn't 3 by 4 or 2 by 5 faces of any size.
+    Size { width: usize, height: usize },
+    /// A face's square on the grid is only partly filled.
+    PartialFace(Vec2),
+    /// There aren't exactly six faces.
+    FaceCount(usize),
+    /// Some faces aren't joined to the rest by an edge.
+    Disconnected,
+    /// Two faces fold onto the same side of the cube.
+    Overlap(Vec2, Vec2),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Size { width, height } => write!(
+                f,
+                "a {width} by {height} grid can't be 3 by 4 or 2 by 5 square faces"
+            ),
+            Self::PartialFace(pos) => write!(f, "the face at {pos:?} is only partly filled"),
+            Self::FaceCount(count) => write!(f, "a cube net has 6 faces, not {count}"),
+            Self::Disconnected => write!(f, "the faces aren't all joined together"),
+            Self::Overlap(a, b) => {
+                write!(f, "the faces at {a:?} and {b:?} fold onto the same side")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+/// The size of the faces of a net whose bounding box is `width` by `height`,
+/// if it is 3 by 4 or 2 by 5 faces.
+pub fn face_size(width: usize, height: usize) -> Option<usize> {
+    let (long, short) = (width.max(height), width.min(height));
+    if long % 4 == 0 && long / 4 * 3 == short {
+        Some(long / 4)
+    } else if long % 5 == 0 && long / 5 * 2 == short {
+        Some(short / 2)
+    } else {
+        None
+    }
+    .filter(|&size| size > 0)
+}
+
+/// A net which folds into a cube.
+#[derive(Clone, Debug)]
+pub struct Net {
+    pub face_size: usize,
+    /// In reading order of their positions.
+    pub faces: [Face; 6],
+    /// Which of the eleven nets this is. Any of them fold the same way, so
+    /// this is only logged.
+    pub shape: Shape,
+}
+
+impl Net {
+    /// Find the net drawn on a grid, with `is_face` true for the cells
+    /// which are part of a face.
+    pub fn from_grid<T>(grid: &Grid<T>, is_face: impl Fn(&T) -> bool) -> Result<Self, NetError> {
+        let (width, height) = (grid.width(), grid.height());
+        let face_size = face_size(width, height).ok_or(NetError::Size { width, height })?;
+        let mut positions = Vec::new();
+        for y in 0..height / face_size {
+            for x in 0..width / face_size {
+                let position = Vec2::new(x as isize, y as isize);
+                let corner = position * face_size;
+                let filled = (0..face_size * face_size)
+                    .filter(|i| {
+                        let offset = Vec2::new((i % face_size) as isize, (i / face_size) as isize);
+                        is_face(&grid[corner + offset])
+                    })
+                    .count();
+                if filled == face_size * face_size {
+                    positions.push(position);
+                } else if filled > 0 {
+                    return Err(NetError::PartialFace(position));
+                }
+            }
+        }
+        Self::from_positions(positions, face_size)
+    }
+
+    /// Fold the faces at `positions`, measured in faces.
+    pub fn from_positions(
+        positions: impl IntoIterator<Item = Vec2>,
+        face_size: usize,
+    ) -> Result<Self, NetError> {
+        let mut positions: Vec<_> = positions.into_iter().collect();
+        positions.sort_unstable_by_key(|pos| (pos.y(), pos.x()));
+        positions.dedup();
+        if positions.len() != 6 {
+            return Err(NetError::FaceCount(positions.len()));
+        }
+        let frames = fold(&positions)?;
+        let faces = std::array::from_fn(|index| {
+            let joins = Edge::ALL.map(|edge| {
+                let outward = frames[index].outward(edge);
+                let face = frames
+                    .iter()
+                    .position(|frame| frame.normal == outward)
+                    .unwrap();
+                let normal = frames[index].normal;
+                let edge_there = Edge::ALL
+                    .into_iter()
+                    .find(|&there| frames[face].outward(there) == normal)
+                    .unwrap();
+                Join {
+                    face,
+                    edge: edge_there,
+                    quarter_turns: (edge_there.index() + 6 - edge.index()) % 4,
+                }
+            });
+            Face {
+                position: positions[index],
+                joins,
+            }
+        });
+        Ok(Self {
+            face_size,
+            faces,
+            shape: shape(&positions),
+        })
+    }
+
+    /// The index of the face at a position on the net, in faces.
+    pub fn face_at(&self, position: Vec2) -> Option<usize> {
+        self.faces.iter().position(|face| face.position == position)
+    }
+}
+
+/// A face's orientation once folded: the direction it faces, and the
+/// directions of the net's right and down across it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Frame {
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Frame {
+    /// The direction out of the face across an edge, once folded.
+    fn outward(self, edge: Edge) -> Vec3 {
+        match edge {
+            Edge::Left => Vec3::ZERO - self.right,
+            Edge::Top => Vec3::ZERO - self.down,
+            Edge::Right => self.right,
+            Edge::Bottom => self.down,
+        }
+    }
+
+    /// The frame of the face across an edge, folded down from this one.
+    fn across(self, edge: Edge) -> Self {
+        let inward = Vec3::ZERO - self.normal;
+        let normal = self.outward(edge);
+        match edge {
+            Edge::Left => Self {
+                normal,
+                right: self.normal,
+                ..self
+            },
+            Edge::Right => Self {
+                normal,
+                right: inward,
+                ..self
+            },
+            Edge::Top => Self {
+                normal,
+                down: self.normal,
+                ..self
+            },
+            Edge::Bottom => Self {
+                normal,
+                down: inward,
+                ..self
+            },
+        }
+    }
+}
+
+/// Fold the faces at sorted `positions` out from the first, returning each
+/// face's frame.
+fn fold(positions: &[Vec2]) -> Result<Vec<Frame>, NetError> {
+    let mut frames = vec![None; positions.len()];
+    frames[0] = Some(Frame {
+        normal: Vec3::new(0, 0, -1),
+        right: Vec3::new(1, 0, 0),
+        down: Vec3::new(0, 1, 0),
+    });
+    let mut stack = vec![0];
+    while let Some(index) = stack.pop() {
+        let frame = frames[index].unwrap();
+        for edge in Edge::ALL {
+            let neighbour = positions[index] + edge.direction();
+            let Ok(next) = positions
+                .binary_search_by_key(&(neighbour.y(), neighbour.x()), |pos| (pos.y(), pos.x()))
+            else {
+                continue;
+            };
+            if frames[next].is_some() {
+                continue;
+            }
+            let folded = frame.across(edge);
+            if let Some(other) = frames
+                .iter()
+                .position(|&f| f.map(|f| f.normal) == Some(folded.normal))
+            {
+                return Err(NetError::Overlap(positions[other], positions[next]));
+            }
+            frames[next] = Some(folded);
+            stack.push(next);
+        }
+    }
+    frames
+        .into_iter()
+        .collect::<Option<_>>()
+        .ok_or(NetError::Disconnected)
+}
+
+/// The positions moved to the origin and put in a standard order, so equal
+/// shapes give equal results.
+fn normalise(mut positions: Vec<Vec2>) -> Vec<Vec2> {
+    if let Some((min, _)) = Vec2::bounding_box(positions.iter().copied()) {
+        for pos in &mut positions {
+            *pos -= min;
+        }
+    }
+    positions.sort_unstable_by_key(|pos| (pos.y(), pos.x()));
+    positions
+}
+
+/// The smallest normalised form of the positions under rotation and
+/// reflection.
+fn canonical(positions: &[Vec2]) -> Vec<Vec2> {
+    let transforms: [fn(Vec2) -> Vec2; 8] = [
+        |p| Vec2::new(p.x(), p.y()),
+        |p| Vec2::new(-p.y(), p.x()),
+        |p| Vec2::new(-p.x(), -p.y()),
+        |p| Vec2::new(p.y(), -p.x()),
+        |p| Vec2::new(-p.x(), p.y()),
+        |p| Vec2::new(p.y(), p.x()),
+        |p| Vec2::new(p.x(), -p.y()),
+        |p| Vec2::new(-p.y(), -p.x()),
+    ];
+    transforms
+        .iter()
+        .map(|transform| normalise(positions.iter().map(|&pos| transform(pos)).collect()))
+        .min_by_key(|form| {
+            form.iter()
+                .map(|pos| (pos.y(), pos.x()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap()
+}
+
+/// Which of [`SHAPES`] a foldable net is.
+fn shape(positions: &[Vec2]) -> Shape {
+    let form = canonical(positions);
+    let shapes: FxHashMap<_, _> = SHAPES
+        .iter()
+        .enumerate()
+        .map(|(index, &(family, drawing))| {
+            let grid = Grid::parse(drawing, |c| c == '#');
+            let positions: Vec<_> = grid
+                .iter()
+                .filter(|(_, &f)| f)
+                .map(|(pos, _)| pos)
+                .collect();
+            let key = canonical(&positions);
+            (
+                key,
+                Shape {
+                    number: index + 1,
+                    family,
+                },
+            )
+        })
+        .collect();
+    shapes[&form]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+
+    /// Every hexomino, ignoring rotations and reflections.
+    fn hexominoes() -> FxHashSet<Vec<Vec2>> {
+        let mut shapes: FxHashSet<Vec<Vec2>> = FxHashSet::from_iter([vec![Vec2::ZERO]]);
+        for _ in 1..6 {
+            shapes = shapes
+                .iter()
+                .flat_map(|shape| {
+                    shape.iter().flat_map(move |&pos| {
+                        Edge::ALL
+                            .map(|edge| pos + edge.direction())
+                            .into_iter()
+                            .filter(|next| !shape.contains(next))
+                            .map(move |next| {
+                                let mut grown = shape.clone();
+                                grown.push(next);
+                                canonical(&grown)
+                            })
+                    })
+                })
+                .collect();
+        }
+        shapes
+    }
+
+    #[test]
+    fn test_eleven_nets() {
+        let hexominoes = hexominoes();
+        assert_eq!(hexominoes.len(), 35);
+        let mut numbers: Vec<_> = hexominoes
+            .into_iter()
+            .filter_map(|positions| Net::from_positions(positions, 1).ok())
+            .map(|net| net.shape.number)
+            .collect();
+        numbers.sort_unstable();
+        assert_eq!(numbers, (1..=11).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_joins() {
+        // The day 22 layout, with faces 2 cells wide.
+        let grid = Grid::parse(
+            "\
+..####
+..####
+..##..
+..##..
+####..
+####..
+##....
+##....",
+            |c| c == '#',
+        );
+        let net = Net::from_grid(&grid, |&f| f).unwrap();
+        assert_eq!(net.face_size, 2);
+        assert_eq!(net.shape.family, Family::TwoThreeOne);
+        for (index, face) in net.faces.iter().enumerate() {
+            for edge in Edge::ALL {
+                let join = face.join(edge);
+                let back = net.faces[join.face].join(join.edge);
+                assert_eq!((back.face, back.edge), (index, edge));
+                assert_eq!((join.quarter_turns + back.quarter_turns) % 4, 0);
+            }
+        }
+        // The top face's top edge joins the left edge of the bottom face.
+        let top = net.face_at(Vec2::new(1, 0)).unwrap();
+        let join = net.faces[top].join(Edge::Top);
+        assert_eq!(net.faces[join.face].position, Vec2::new(0, 3));
+        assert_eq!(join.edge, Edge::Left);
+        assert_eq!(join.quarter_turns, 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |drawing| Net::from_grid(&Grid::parse(drawing, |c| c == '#'), |&f| f);
+        assert_eq!(
+            parse("###\n###").unwrap_err(),
+            NetError::Size {
+                width: 3,
+                height: 2
+            }
+        );
+        assert_eq!(
+            parse("####\n####\n#...").unwrap_err(),
+            NetError::FaceCount(9)
+        );
+        assert_eq!(
+            parse("####\n#...\n.#..").unwrap_err(),
+            NetError::Disconnected
+        );
+        assert!(matches!(
+            parse("#####...\n#####...\n........\n........\n........\n........"),
+            Err(NetError::PartialFace(_))
+        ));
+        assert!(matches!(
+            parse("##..\n##..\n##.."),
+            Err(NetError::Overlap(..))
+        ));
+    }
+}
@@ -0,0 +1,175 @@
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+/// A sequence of states which repeats: the state after `start + period`
+/// steps is the same as after `start` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The step before `n` which reaches the same state.
+    pub const fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// A counter's value after `n` steps, given its values after each step up
+    /// to at least `start + period`. The counter must go up by the same
+    /// amount every time round the cycle.
+    pub fn extrapolate(&self, history: &[isize], n: usize) -> isize {
+        if let Some(&value) = history.get(n) {
+            return value;
+        }
+        let per_cycle = history[self.start + self.period] - history[self.start];
+        let cycles = ((n - self.start) / self.period) as isize;
+        history[self.equivalent_step(n)] + cycles * per_cycle
+    }
+}
+
+/// Find a cycle in the states reached by repeatedly applying `step`, using
+/// Floyd's tortoise and hare. This only keeps two states at a time.
+#[allow(dead_code)]
+pub fn floyd<S: Clone + PartialEq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Find a cycle in the states reached by repeatedly applying `step`, using
+/// Brent's algorithm. This only keeps two states at a time, and usually
+/// calls `step` less often than [`floyd`].
+#[allow(dead_code)]
+pub fn brent<S: Clone + PartialEq>(initial: S, step: impl Fn(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Finds a cycle in a simulation by remembering a fingerprint of every state.
+/// Unlike [`floyd`] and [`brent`], this works for simulations which can only
+/// be stepped forwards in place.
+pub struct CycleDetector<K> {
+    seen: FxHashMap<K, usize>,
+    history: Vec<isize>,
+    cycle: Option<Cycle>,
+}
+
+impl<K: Eq + Hash> CycleDetector<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: FxHashMap::default(),
+            history: Vec::new(),
+            cycle: None,
+        }
+    }
+
+    /// Record the state after the next step (starting from step 0), with a
+    /// counter of progress through the simulation. Returns the cycle once a
+    /// fingerprint repeats.
+    pub fn record(&mut self, fingerprint: K, progress: isize) -> Option<Cycle> {
+        if self.cycle.is_none() {
+            let step = self.history.len();
+            self.history.push(progress);
+            if let Some(start) = self.seen.insert(fingerprint, step) {
+                self.cycle = Some(Cycle {
+                    start,
+                    period: step - start,
+                });
+            }
+        }
+        self.cycle
+    }
+
+    /// The progress counter after `n` steps, once a cycle has been found.
+    pub fn extrapolate(&self, n: usize) -> Option<isize> {
+        self.cycle.map(|cycle| cycle.extrapolate(&self.history, n))
+    }
+}
+
+impl<K: Eq + Hash> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles() {
+        let step = |&x: &usize| (x * x + 1) % 255;
+        let mut detector = CycleDetector::new();
+        let mut x = 3;
+        let mut total = 0;
+        let found = loop {
+            total += x as isize;
+            if let Some(cycle) = detector.record(x, total) {
+                break cycle;
+            }
+            x = step(&x);
+        };
+        let expected = Cycle {
+            start: 2,
+            period: 6,
+        };
+        assert_eq!(found, expected);
+        assert_eq!(floyd(3, step), found);
+        assert_eq!(brent(3, step), found);
+
+        let mut x = 3;
+        let mut total = 0;
+        for _ in 0..=1000 {
+            total += x as isize;
+            x = step(&x);
+        }
+        assert_eq!(detector.extrapolate(1000), Some(total));
+        assert_eq!(detector.extrapolate(1), Some(3 + 10));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::util::max_n;
+use crate::util::select::max_k;
 use crate::Day;
 
 #[derive(Clone)]
@@ -24,9 +24,6 @@ impl Day for Day1 {
     }
 
     fn part2(&self) -> String {
-        max_n::<3, _>(&self.0)
-            .into_iter()
-            .sum::<usize>()
-            .to_string()
+        max_k(&self.0, 3).into_iter().sum::<usize>().to_string()
     }
 }
@@ -1,21 +1,22 @@
+use crate::grid::Grid;
 use crate::vec2::{Vec2, CARDINALS};
 use crate::Day;
 
 #[derive(Clone)]
-pub struct Day8(Vec<Vec<usize>>);
+pub struct Day8(Grid<usize>);
 
 #[inline]
-fn trees_visible(from: Vec2, facing: Vec2, map: &[Vec<usize>]) -> (usize, bool) {
+fn trees_visible(from: Vec2, facing: Vec2, map: &Grid<usize>) -> (usize, bool) {
     let mut pos = from;
     let mut trees = 0;
-    let height = from.index(map);
+    let height = map[from];
     loop {
         pos += facing;
-        if 0 > pos.0 || 0 > pos.1 || pos.0 >= map[0].len() as isize || pos.1 >= map.len() as isize {
+        let Some(&tree) = map.get(pos) else {
             return (trees, false);
-        }
+        };
         trees += 1;
-        if pos.index(map) >= height {
+        if tree >= height {
             return (trees, true);
         }
     }
@@ -23,16 +24,12 @@ fn trees_visible(from: Vec2, facing: Vec2, map: &[Vec<usize>]) -> (usize, bool)
 
 impl Day for Day8 {
     fn parse(input: &str) -> Self {
-        Self(
-            input
-                .lines()
-                .map(|line| line.chars().map(|c| c as usize - '0' as usize).collect())
-                .collect(),
-        )
+        Self(Grid::parse(input, |c| c as usize - '0' as usize))
     }
 
     fn part1(&self) -> String {
-        Vec2::map_coords(&self.0)
+        self.0
+            .positions()
             .filter(|location| {
                 CARDINALS
                     .iter()
@@ -43,7 +40,8 @@ impl Day for Day8 {
     }
 
     fn part2(&self) -> String {
-        Vec2::map_coords(&self.0)
+        self.0
+            .positions()
             .map(|location| {
                 CARDINALS
                     .iter()
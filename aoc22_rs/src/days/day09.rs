@@ -1,4 +1,8 @@
+use rustc_hash::FxHashSet;
+
+use crate::grid::Grid;
 use crate::vec2::Vec2;
+use crate::visualize::{Recorder, Visualize};
 use crate::Day;
 
 use std::collections::BTreeSet;
@@ -9,7 +13,7 @@ pub struct Day9(Vec<Vec2>);
 #[inline]
 fn move_towards(vec: Vec2, target: Vec2) -> Vec2 {
     let delta = target - vec;
-    if -1 <= delta.0 && delta.0 <= 1 && -1 <= delta.1 && delta.1 <= 1 {
+    if delta.chebyshev() <= 1 {
         vec
     } else {
         vec + delta.signum()
@@ -18,7 +22,7 @@ fn move_towards(vec: Vec2, target: Vec2) -> Vec2 {
 
 #[inline]
 fn simulate_knot_movements<const N: usize>(movements: &[Vec2]) -> usize {
-    let mut knots = [Vec2::new(0, 0); N];
+    let mut knots = [Vec2::ZERO; N];
     let mut seen = BTreeSet::new();
     let x_range = movements.len() as isize + 1;
     seen.insert(knots[0].as_base_n(x_range));
@@ -32,6 +36,40 @@ fn simulate_knot_movements<const N: usize>(movements: &[Vec2]) -> usize {
     seen.len()
 }
 
+/// Draw the knots (numbered from the head) and the places the tail has
+/// visited, with y pointing up.
+fn draw(knots: &[Vec2], visited: &FxHashSet<Vec2>, recorder: &mut Recorder) {
+    let (min, max) = Vec2::bounding_box(knots.iter().chain(visited).copied()).unwrap();
+    let size = max - min + Vec2::new(1, 1);
+    let grid = Grid::from_fn(size.x() as usize, size.y() as usize, |pos| {
+        let pos = Vec2::new(min.x() + pos.x(), max.y() - pos.y());
+        match knots.iter().position(|&knot| knot == pos) {
+            Some(index) => char::from_digit(index as u32, 10).unwrap(),
+            None if visited.contains(&pos) => '#',
+            None => '.',
+        }
+    });
+    recorder.record_at(Vec2::new(min.x(), -max.y()), grid);
+}
+
+impl Visualize for Day9 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        let mut knots = [Vec2::ZERO; 10];
+        let mut visited = FxHashSet::default();
+        visited.insert(Vec2::ZERO);
+        for (index, &direction) in self.0.iter().enumerate() {
+            knots[0] += direction;
+            for i in 1..knots.len() {
+                knots[i] = move_towards(knots[i], knots[i - 1]);
+            }
+            visited.insert(knots[knots.len() - 1]);
+            if index % 50 == 0 || index == self.0.len() - 1 {
+                draw(&knots, &visited, recorder);
+            }
+        }
+    }
+}
+
 impl Day for Day9 {
     fn parse(input: &str) -> Self {
         Self(
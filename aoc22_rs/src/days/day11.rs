@@ -1,4 +1,4 @@
-use crate::{util::max_n, Day};
+use crate::{util::select::max_k, Day};
 
 #[derive(Debug, Clone)]
 pub struct Day11 {
@@ -25,7 +25,7 @@ impl Day11 {
                 }
             }
         }
-        max_n::<2, _>(activity).into_iter().product()
+        max_k(activity, 2).into_iter().product()
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::{util::max_n, Day};
+use crate::{util::select::max_n, Day};
 
 #[derive(Debug, Clone)]
 pub struct Day11 {
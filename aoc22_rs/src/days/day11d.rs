@@ -1,6 +1,6 @@
 use std::thread;
 
-use crate::{util::max_n, Day};
+use crate::{util::select::max_n, Day};
 
 #[derive(Debug, Clone)]
 pub struct Day11 {
@@ -1,8 +1,10 @@
-use crate::vec2::{Vec2, CARDINALS};
+use crate::grid::Grid;
+use crate::search::{self, SearchProblem};
+use crate::vec2::Vec2;
 use crate::Day;
 
 #[derive(Clone)]
-pub struct Day12(Vec<Vec<Tile>>);
+pub struct Day12(Grid<Tile>);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Tile {
@@ -35,54 +37,52 @@ impl Tile {
 
 impl Day12 {
     fn find_end(&self) -> Vec2 {
-        Vec2::map_coords(&self.0)
-            .find(|pos| *pos.index(&self.0) == Tile::End)
-            .unwrap()
+        self.0.find(|&tile| tile == Tile::End).unwrap()
     }
 
     fn shortest_path_back_to(&self, pred: impl Fn(Tile) -> bool) -> usize {
-        let mut open = vec![self.find_end()];
-        let mut visited = vec![0u128; self.0.len()];
-        let mut dist = 0;
-        loop {
-            let mut new_open = Vec::new();
-            for pos in open {
-                if visited[pos.1 as usize] & (1 << pos.0) != 0 {
-                    continue;
-                }
-                visited[pos.1 as usize] |= 1 << pos.0;
-                if pred(*pos.index(&self.0)) {
-                    return dist;
-                }
-                let height = pos.index(&self.0).height();
-                for dir in CARDINALS {
-                    let new_pos = pos + dir;
-                    if height <= new_pos.index(&self.0).height() + 1 {
-                        new_open.push(new_pos);
-                    }
-                }
-            }
-            open = new_open;
-            dist += 1;
-        }
+        let climb = ClimbBack { map: self, pred };
+        search::bfs(&climb).unwrap().len()
+    }
+}
+
+/// Walking back down from the end to a tile matching `pred`.
+struct ClimbBack<'a, F> {
+    map: &'a Day12,
+    pred: F,
+}
+
+impl<F: Fn(Tile) -> bool> SearchProblem for ClimbBack<'_, F> {
+    type State = Vec2;
+
+    fn start(&self) -> Vec2 {
+        self.map.find_end()
+    }
+
+    fn is_goal(&self, pos: &Vec2) -> bool {
+        (self.pred)(self.map.0[*pos])
+    }
+
+    fn successors(&self, &pos: &Vec2) -> impl Iterator<Item = (Vec2, usize)> {
+        let grid = &self.map.0;
+        let height = grid[pos].height();
+        grid.cardinal_neighbours(pos)
+            .filter(move |&new_pos| height <= grid[new_pos].height() + 1)
+            .map(|new_pos| (new_pos, 1))
+    }
+
+    fn state_count(&self) -> Option<usize> {
+        Some(self.map.0.width() * self.map.0.height())
+    }
+
+    fn index(&self, &pos: &Vec2) -> usize {
+        self.map.0.offset(pos).unwrap()
     }
 }
 
 impl Day for Day12 {
     fn parse(input: &str) -> Self {
-        let fence = Tile::Height(Tile::MIN_HEIGHT - 2);
-        let mut map: Vec<Vec<_>> = input
-            .lines()
-            .map(|line| {
-                std::iter::once(fence)
-                    .chain(line.chars().map(Tile::parse))
-                    .chain(Some(fence))
-                    .collect()
-            })
-            .collect();
-        map.push(vec![fence; map[0].len()]);
-        map.insert(0, vec![fence; map[0].len()]);
-        Self(map)
+        Self(Grid::parse(input, Tile::parse))
     }
 
     fn part1(&self) -> String {
@@ -1,10 +1,14 @@
-use rustc_hash::FxHashSet;
-
-use crate::{vec2::Vec2, Day};
+use crate::{
+    grid::Grid,
+    pointset::{DenseSet, PointSet},
+    vec2::Vec2,
+    visualize::{Recorder, Visualize},
+    Day,
+};
 
 #[derive(Clone)]
 pub struct Day14 {
-    map: FxHashSet<Vec2>,
+    map: DenseSet,
     min_x: isize,
     max_x: isize,
     max_y: isize,
@@ -15,18 +19,18 @@ const FALLS: [Vec2; 3] = [Vec2::new(0, 1), Vec2::new(-1, 1), Vec2::new(1, 1)];
 
 impl Day14 {
     fn fall_sand(&mut self, from: Vec2, hard_bottom: bool) -> Option<Vec<Vec2>> {
-        if self.map.get(&from).is_some() {
+        if self.map.contains(from) {
             return None;
         }
         let mut point = from;
         let mut trace = Vec::new();
-        'one_fall: while (self.min_x..=self.max_x).contains(&point.0) && point.1 < self.max_y {
-            if self.map.get(&point).is_some() {
+        'one_fall: while (self.min_x..=self.max_x).contains(&point.x()) && point.y() < self.max_y {
+            if self.map.contains(point) {
                 break;
             }
             for &fall in &FALLS {
                 let new_point = point + fall;
-                if self.map.get(&new_point).is_none() {
+                if !self.map.contains(new_point) {
                     trace.push(point);
                     point = new_point;
                     continue 'one_fall;
@@ -35,14 +39,16 @@ impl Day14 {
             self.map.insert(point);
             return Some(trace);
         }
-        if hard_bottom && point.1 >= self.max_y {
+        if hard_bottom && point.y() >= self.max_y {
             self.map.insert(point);
             return Some(trace);
         }
         None
     }
 
-    fn count_settle(&self, hard_bottom: bool) -> usize {
+    /// Count the grains of sand which settle, calling `on_settle` with the
+    /// map and count after each one.
+    fn count_settle(&self, hard_bottom: bool, mut on_settle: impl FnMut(&Self, usize)) -> usize {
         let mut map = self.clone();
         let mut total = 0;
         let mut stack = vec![SPAWN];
@@ -51,15 +57,42 @@ impl Day14 {
                 continue;
             };
             total += 1;
+            on_settle(&map, total);
             stack.extend(trace);
         }
         total
     }
+
+    /// Draw rock from the original map and sand from `map`.
+    fn draw(&self, map: &Self) -> Grid<char> {
+        let width = (self.max_x - self.min_x + 1) as usize;
+        Grid::from_fn(width, self.max_y as usize + 1, |pos| {
+            let pos = pos + Vec2::new(self.min_x, 0);
+            if self.map.contains(pos) {
+                '#'
+            } else if map.map.contains(pos) {
+                'o'
+            } else {
+                ' '
+            }
+        })
+    }
+}
+
+impl Visualize for Day14 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        // Show part 2, finishing when the sand reaches the spawn point.
+        self.count_settle(true, |map, count| {
+            if count % 250 == 0 || map.map.contains(SPAWN) {
+                recorder.record(self.draw(map));
+            }
+        });
+    }
 }
 
 impl Day for Day14 {
     fn parse(input: &str) -> Self {
-        let mut map = FxHashSet::default();
+        let mut rocks = Vec::new();
         let (mut min_x, mut max_x, mut max_y) = (isize::MAX, isize::MIN, 0);
         for line in input.lines() {
             let mut last_point: Option<Vec2> = None;
@@ -72,27 +105,30 @@ impl Day for Day14 {
                 max_y = max_y.max(y);
                 let point = Vec2::new(x, y);
                 if let Some(last_point) = last_point {
-                    for point in last_point.range_inclusive(point) {
-                        map.insert(point);
-                    }
+                    rocks.extend(last_point.range_inclusive(point));
                 }
                 last_point = Some(point);
             }
         }
         max_y += 1;
+        let (min_x, max_x) = (min_x - max_y, max_x + max_y);
+        let mut map = DenseSet::new(Vec2::new(min_x, 0), Vec2::new(max_x, max_y));
+        for rock in rocks {
+            map.insert(rock);
+        }
         Self {
             map,
-            min_x: min_x - max_y,
-            max_x: max_x + max_y,
+            min_x,
+            max_x,
             max_y,
         }
     }
 
     fn part1(&self) -> String {
-        self.count_settle(false).to_string()
+        self.count_settle(false, |_, _| {}).to_string()
     }
 
     fn part2(&self) -> String {
-        self.count_settle(true).to_string()
+        self.count_settle(true, |_, _| {}).to_string()
     }
 }
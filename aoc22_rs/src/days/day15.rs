@@ -1,4 +1,11 @@
-use crate::{vec2::Vec2, Day};
+use crate::{
+    intervalset::IntervalSet,
+    vec2::{
+        transform::{Invert, Rotate45, Transform},
+        Vec2,
+    },
+    Day,
+};
 
 const PREFIX: &str = "Sensor at x=";
 const COORD_SEP: &str = ", y=";
@@ -15,22 +22,26 @@ fn parse_coord(input: &str) -> Vec2<Unshifted> {
     Vec2::new(x, y)
 }
 
-const fn shift_coord(coord: Vec2<Unshifted>) -> Vec2<Shifted> {
-    Vec2::new(coord.0 - coord.1, coord.0 + coord.1)
-}
-
-const fn unshift_coord(coord: Vec2<Shifted>) -> Vec2<Unshifted> {
-    let y = (coord.1 - coord.0) / 2;
-    let x = coord.0 + y;
-    Vec2::new(x, y)
-}
-
+/// Coordinates rotated by 45°, so that the diamond covered by each sensor
+/// becomes a square.
 #[derive(Default, Copy, Clone, PartialEq, Eq)]
 struct Shifted;
 
 #[derive(Default, Copy, Clone, PartialEq, Eq, Debug)]
 struct Unshifted;
 
+impl From<Vec2<Unshifted>> for Vec2<Shifted> {
+    fn from(coord: Vec2<Unshifted>) -> Self {
+        Rotate45::new().apply(coord)
+    }
+}
+
+impl From<Vec2<Shifted>> for Vec2<Unshifted> {
+    fn from(coord: Vec2<Shifted>) -> Self {
+        Rotate45::<Unshifted, Shifted>::new().inverse().apply(coord)
+    }
+}
+
 #[derive(Copy, Clone)]
 struct UnshiftedSensor {
     pos: Vec2<Unshifted>,
@@ -39,10 +50,11 @@ struct UnshiftedSensor {
 
 impl UnshiftedSensor {
     const fn range_in_row(&self, row: isize) -> Option<std::ops::RangeInclusive<isize>> {
-        let max_x_dist = (self.pos.0 - self.beacon.0).abs() + (self.pos.1 - self.beacon.1).abs()
-            - (self.pos.1 - row).abs();
+        let max_x_dist = (self.pos.x() - self.beacon.x()).abs()
+            + (self.pos.y() - self.beacon.y()).abs()
+            - (self.pos.y() - row).abs();
         if max_x_dist > 0 {
-            Some((self.pos.0 - max_x_dist)..=(self.pos.0 + max_x_dist))
+            Some((self.pos.x() - max_x_dist)..=(self.pos.x() + max_x_dist))
         } else {
             None
         }
@@ -68,8 +80,8 @@ impl From<UnshiftedSensor> for ShiftedSensor {
         let left_corner = unshifted.pos - Vec2::new(distance as isize, 0);
         let right_corner = unshifted.pos + Vec2::new(distance as isize, 0);
         Self {
-            top_left: shift_coord(left_corner),
-            bottom_right: shift_coord(right_corner),
+            top_left: left_corner.into(),
+            bottom_right: right_corner.into(),
         }
     }
 }
@@ -77,18 +89,18 @@ impl From<UnshiftedSensor> for ShiftedSensor {
 impl ShiftedSensor {
     fn intersections(&self, other: Self) -> Vec<Vec2<Shifted>> {
         [
-            self.top_left.0,
-            self.bottom_right.0,
-            other.top_left.0,
-            other.bottom_right.0,
+            self.top_left.x(),
+            self.bottom_right.x(),
+            other.top_left.x(),
+            other.bottom_right.x(),
         ]
         .into_iter()
         .flat_map(|x| {
             [
-                self.top_left.1,
-                self.bottom_right.1,
-                other.top_left.1,
-                other.bottom_right.1,
+                self.top_left.y(),
+                self.bottom_right.y(),
+                other.top_left.y(),
+                other.bottom_right.y(),
             ]
             .into_iter()
             .map(move |y| Vec2::new(x, y))
@@ -97,34 +109,7 @@ impl ShiftedSensor {
     }
 
     fn contains(&self, point: Vec2<Shifted>) -> bool {
-        (self.top_left.0..=self.bottom_right.0).contains(&point.0)
-            && (self.top_left.1..=self.bottom_right.1).contains(&point.1)
-    }
-}
-
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
-enum RangeEnd {
-    Open(isize),
-    Close(isize),
-}
-
-impl RangeEnd {
-    const fn value(&self) -> isize {
-        match self {
-            Self::Open(v) | Self::Close(v) => *v,
-        }
-    }
-}
-
-impl PartialOrd for RangeEnd {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for RangeEnd {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.value().cmp(&other.value())
+        point.within(self.top_left, self.bottom_right)
     }
 }
 
@@ -144,39 +129,23 @@ impl Day for Day15 {
     }
 
     fn part1(&self) -> String {
-        let mut points_of_interest: Vec<_> = self
+        const ROW: isize = 2_000_000;
+        let covered: IntervalSet<isize> = self
             .0
             .iter()
-            .filter_map(|sensor| sensor.range_in_row(2_000_000))
-            .flat_map(|range| {
-                [
-                    RangeEnd::Open(*range.start()),
-                    RangeEnd::Close(*range.end()),
-                ]
-            })
+            .filter_map(|sensor| sensor.range_in_row(ROW))
+            .map(|range| *range.start()..*range.end() + 1)
             .collect();
-        points_of_interest.sort();
-        let (mut total, mut depth, mut top_open) = (0, 0, 42);
-        for point in points_of_interest {
-            match point {
-                RangeEnd::Open(open) => {
-                    if depth == 0 {
-                        assert!(top_open == 42);
-                        top_open = open;
-                    }
-                    depth += 1;
-                }
-                RangeEnd::Close(close) => {
-                    depth -= 1;
-                    if depth == 0 {
-                        assert!(top_open != 42);
-                        total += close - top_open;
-                        top_open = 42;
-                    }
-                }
-            }
-        }
-        total.to_string()
+        let mut beacons: Vec<_> = self
+            .0
+            .iter()
+            .filter(|sensor| sensor.beacon.y() == ROW)
+            .map(|sensor| sensor.beacon.x())
+            .collect();
+        beacons.sort_unstable();
+        beacons.dedup();
+        let beacons = beacons.into_iter().filter(|&x| covered.contains(x)).count();
+        (covered.total_length() - beacons as isize).to_string()
     }
 
     fn part2(&self) -> String {
@@ -189,9 +158,9 @@ impl Day for Day15 {
             .flat_map(|(sensor, other)| sensor.intersections(*other))
             .flat_map(|point| AROUND.iter().map(move |offset| point + *offset))
             .filter(|point| !shifted.iter().any(|sensor| sensor.contains(*point)))
-            .map(unshift_coord)
-            .find(|point| (0..=SIZE).contains(&point.0) && (0..=SIZE).contains(&point.1))
-            .map(|point| (SIZE * point.0 + point.1).to_string())
+            .map(Vec2::<Unshifted>::from)
+            .find(|point| (0..=SIZE).contains(&point.x()) && (0..=SIZE).contains(&point.y()))
+            .map(|point| (SIZE * point.x() + point.y()).to_string())
             .unwrap()
     }
 }
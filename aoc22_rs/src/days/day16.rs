@@ -1,12 +1,134 @@
 use rustc_hash::FxHashMap;
-use std::collections::{BinaryHeap, VecDeque};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
 
-use crate::Day;
+use crate::{
+    explain::Explain,
+    optimize::{branch_and_bound, branch_and_bound_path, BranchAndBound},
+    Day,
+};
+
+/// The valve every agent starts at in the puzzle.
+const START: &str = "AA";
 
 #[derive(Clone)]
 pub struct Day16 {
+    /// The full tunnel system, for other scenarios and explaining.
+    raw: RawSystem,
+    /// The network starting from [`START`], for the puzzle's own scenarios.
+    network: Network,
+}
+
+/// One agent's starting valve and time budget. Every budget counts down to
+/// the same eruption, so an agent with less time joins later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Agent {
+    pub start: String,
+    pub minutes: usize,
+}
+
+impl FromStr for Agent {
+    type Err = String;
+
+    /// Parse an agent written as `START:MINUTES`, like `AA:26`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, minutes) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected VALVE:MINUTES, got {s}"))?;
+        let minutes = minutes
+            .parse()
+            .map_err(|_| format!("invalid number of minutes: {minutes}"))?;
+        Ok(Self {
+            start: start.to_string(),
+            minutes,
+        })
+    }
+}
+
+/// A valve-opening problem: who is opening valves, and how long it takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scenario {
+    pub agents: Vec<Agent>,
+    /// The minutes it takes to open a valve once an agent reaches it.
+    pub opening_cost: usize,
+}
+
+impl Scenario {
+    /// The puzzle's scenario: `agents` agents starting at [`START`] with
+    /// `minutes` each, taking a minute to open each valve.
+    pub fn puzzle(agents: usize, minutes: usize) -> Self {
+        let agent = Agent {
+            start: START.to_string(),
+            minutes,
+        };
+        Self {
+            agents: vec![agent; agents],
+            opening_cost: 1,
+        }
+    }
+
+    fn minutes(&self) -> usize {
+        self.agents
+            .iter()
+            .map(|agent| agent.minutes)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// How to share the valves between several agents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Method {
+    /// Branch and bound over the agents' routes, one agent after another.
+    Search,
+    /// Find the best release for every subset of valves for each agent on
+    /// their own, then the best way to split the valves into disjoint subsets.
+    Subsets,
+}
+
+/// The best way found to open the valves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub released: usize,
+    /// In order of agent, then minute.
+    pub openings: Vec<Opening>,
+}
+
+/// An agent finishing opening a valve, on the shared clock from minute 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opening {
+    pub agent: usize,
+    pub valve: &'static str,
+    pub minute: usize,
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for opening in &self.openings {
+            writeln!(
+                f,
+                "Agent {} opens valve {} in minute {}.",
+                opening.agent + 1,
+                opening.valve,
+                opening.minute
+            )?;
+        }
+        write!(f, "Total pressure released: {}", self.released)
+    }
+}
+
+/// The valves worth opening, plus any starting valves, and the distances
+/// between them. Valves worth opening come first, so sets of them fit in a
+/// bitmask of their indices.
+#[derive(Clone, Debug)]
+struct Network {
     valves: Vec<Valve>,
-    starting_valve: ValveIdx,
+    names: Vec<&'static str>,
+    /// The number of valves with any flow.
+    openable: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -54,6 +176,7 @@ impl ValveSet {
 }
 */
 
+#[derive(Clone)]
 struct RawSystem {
     valves: FxHashMap<&'static str, RawValve>,
 }
@@ -64,6 +187,7 @@ impl RawSystem {
         Self { valves }
     }
 
+    /// The number of tunnels between a valve and each valve reachable from it.
     fn distances(&self, valve: &'static str) -> FxHashMap<&'static str, usize> {
         let mut distances = FxHashMap::default();
         let mut queue = VecDeque::new();
@@ -72,15 +196,41 @@ impl RawSystem {
         while let Some((valve, distance)) = queue.pop_front() {
             for tunnel in &self.valves[valve].tunnels {
                 if !distances.contains_key(tunnel) {
-                    distances.insert(tunnel, distance + 2);
+                    distances.insert(tunnel, distance + 1);
                     queue.push_back((tunnel, distance + 1));
                 }
             }
         }
         distances
     }
+
+    /// The valves passed through on a shortest route between two valves,
+    /// ending with `to`.
+    fn route(&self, from: &'static str, to: &'static str) -> Vec<&'static str> {
+        let mut previous = FxHashMap::default();
+        let mut queue = VecDeque::from([from]);
+        while let Some(valve) = queue.pop_front() {
+            if valve == to {
+                break;
+            }
+            for &tunnel in &self.valves[valve].tunnels {
+                if tunnel != from && !previous.contains_key(tunnel) {
+                    previous.insert(tunnel, valve);
+                    queue.push_back(tunnel);
+                }
+            }
+        }
+        let mut route = vec![to];
+        while let Some(&valve) = previous.get(route.last().unwrap()) {
+            route.push(valve);
+        }
+        route.pop();
+        route.reverse();
+        route
+    }
 }
 
+#[derive(Clone)]
 struct RawValve {
     flow: usize,
     tunnels: Vec<&'static str>,
@@ -108,28 +258,33 @@ impl RawValve {
     }
 }
 
-impl TryFrom<RawSystem> for Day16 {
-    type Error = ();
-
-    fn try_from(raw: RawSystem) -> Result<Self, Self::Error> {
-        let names: Vec<&'static str> = raw
+impl Network {
+    /// Build the network of valves with flow, plus `starts`.
+    fn new(raw: &RawSystem, starts: &[&str]) -> Result<Self, String> {
+        let mut names: Vec<&'static str> = raw
             .valves
             .iter()
-            .filter(|(&name, valve)| name == "AA" || valve.flow != 0)
+            .filter(|(_, valve)| valve.flow != 0)
             .map(|(name, _)| *name)
             .collect();
-        let mut starting_valve = None;
+        names.sort_unstable();
+        let openable = names.len();
+        for start in starts {
+            let (&name, _) = raw
+                .valves
+                .get_key_value(*start)
+                .ok_or_else(|| format!("no valve named {start}"))?;
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
         let valves = names
             .iter()
-            .enumerate()
-            .map(|(idx, name)| {
-                if name == &"AA" {
-                    starting_valve = Some(ValveIdx(idx));
-                }
+            .map(|name| {
                 let raw_distances = raw.distances(name);
                 let distances = names
                     .iter()
-                    .filter_map(|name| raw_distances.get(name).copied())
+                    .map(|name| raw_distances.get(name).copied().unwrap_or(usize::MAX / 2))
                     .collect();
                 Valve {
                     flow: raw.valves[name].flow,
@@ -137,136 +292,586 @@ impl TryFrom<RawSystem> for Day16 {
                 }
             })
             .collect();
-        starting_valve
-            .map(|starting_valve| Self {
-                valves,
-                starting_valve,
-            })
-            .ok_or(())
+        Ok(Self {
+            valves,
+            names,
+            openable,
+        })
     }
-}
 
-impl Day16 {
     fn valve(&self, idx: ValveIdx) -> &Valve {
         &self.valves[idx.0]
     }
+
+    fn index_of(&self, name: &str) -> ValveIdx {
+        ValveIdx(self.names.iter().position(|&n| n == name).unwrap())
+    }
+
+    /// The best release by one agent for every set of valves, as a table
+    /// indexed by bitmask, where each entry allows opening any subset of it.
+    fn subset_releases(&self, start: ValveIdx, minutes: usize, opening_cost: usize) -> Vec<usize> {
+        fn visit(
+            network: &Network,
+            opening_cost: usize,
+            (location, turns_remaining, opened, released): (ValveIdx, usize, usize, usize),
+            best: &mut [usize],
+        ) {
+            best[opened] = best[opened].max(released);
+            for valve in (0..network.openable).map(ValveIdx) {
+                let cost = network.valve(location).distance_to(valve) + opening_cost;
+                if opened & 1 << valve.0 != 0 || cost >= turns_remaining {
+                    continue;
+                }
+                let turns_remaining = turns_remaining - cost;
+                let released = released + network.valve(valve).flow * turns_remaining;
+                let state = (valve, turns_remaining, opened | 1 << valve.0, released);
+                visit(network, opening_cost, state, best);
+            }
+        }
+
+        let mut best = vec![0; 1 << self.openable];
+        visit(self, opening_cost, (start, minutes, 0, 0), &mut best);
+        for bit in (0..self.openable).map(|valve| 1 << valve) {
+            for mask in 0..best.len() {
+                if mask & bit != 0 {
+                    best[mask] = best[mask].max(best[mask ^ bit]);
+                }
+            }
+        }
+        best
+    }
+}
+
+/// The best way to split `mask` between `one`, getting a subset of it, and
+/// `rest`, getting the remaining valves. Returns the release and `one`'s
+/// subset.
+fn best_split(one: &[usize], rest: &[usize], mask: usize) -> (usize, usize) {
+    let mut best = (rest[mask] + one[0], 0);
+    // Iterate over the non-empty subsets of `mask`.
+    let mut subset = mask;
+    while subset != 0 {
+        best = best.max((one[subset] + rest[mask ^ subset], subset));
+        subset = (subset - 1) & mask;
+    }
+    best
+}
+
+impl Day16 {
+    /// The network for a scenario, reusing the puzzle's if it has the same
+    /// starting valves.
+    fn network(&self, scenario: &Scenario) -> Result<Cow<'_, Network>, String> {
+        if scenario.agents.iter().all(|agent| agent.start == START) {
+            Ok(Cow::Borrowed(&self.network))
+        } else {
+            let starts: Vec<_> = scenario.agents.iter().map(|agent| &*agent.start).collect();
+            Network::new(&self.raw, &starts).map(Cow::Owned)
+        }
+    }
+
+    /// The most pressure which can be released in a scenario.
+    pub fn max_release(&self, scenario: &Scenario, method: Method) -> Result<usize, String> {
+        let network = self.network(scenario)?;
+        let release = Release::new(&network, scenario)?;
+        match method {
+            Method::Search => Ok(release.best()),
+            Method::Subsets => release.best_subsets().map(|(released, _)| released),
+        }
+    }
+
+    /// The best plan for a scenario, with when each valve is opened.
+    pub fn plan(&self, scenario: &Scenario, method: Method) -> Result<Plan, String> {
+        let network = self.network(scenario)?;
+        let release = Release::new(&network, scenario)?;
+        match method {
+            Method::Search => Ok(release.plan(u64::MAX)),
+            Method::Subsets => release.plan_by_subsets(),
+        }
+    }
+
+    /// Describe the best plan minute by minute, in the puzzle's own words.
+    fn explain(&self, scenario: &Scenario) -> String {
+        let plan = self.plan(scenario, Method::Search).unwrap();
+        let minutes = scenario.minutes();
+        let mut actions = vec![Vec::new(); minutes + 1];
+        for (index, agent) in scenario.agents.iter().enumerate() {
+            let (name, moves, opens) = match index {
+                0 => ("You".to_string(), "move to", "open"),
+                1 => ("The elephant".to_string(), "moves to", "opens"),
+                _ => (format!("Elephant {index}"), "moves to", "opens"),
+            };
+            let mut location = *self.raw.valves.get_key_value(&*agent.start).unwrap().0;
+            let mut minute = minutes - agent.minutes;
+            for opening in plan
+                .openings
+                .iter()
+                .filter(|opening| opening.agent == index)
+            {
+                for valve in self.raw.route(location, opening.valve) {
+                    minute += 1;
+                    actions[minute].push(format!("{name} {moves} valve {valve}."));
+                }
+                while minute < opening.minute {
+                    minute += 1;
+                    actions[minute].push(format!("{name} {opens} valve {}.", opening.valve));
+                }
+                location = opening.valve;
+            }
+        }
+
+        let mut explanation = String::new();
+        for (minute, actions) in actions.iter().enumerate().skip(1) {
+            let mut open: Vec<_> = plan
+                .openings
+                .iter()
+                .filter(|opening| opening.minute < minute)
+                .map(|opening| opening.valve)
+                .collect();
+            open.sort_unstable();
+            let pressure: usize = open.iter().map(|name| self.raw.valves[name].flow).sum();
+            let valves = match &open[..] {
+                [] => "No valves are open.".to_string(),
+                [name] => format!("Valve {name} is open, releasing {pressure} pressure."),
+                [first, second] => {
+                    format!("Valves {first} and {second} are open, releasing {pressure} pressure.")
+                }
+                [rest @ .., last] => format!(
+                    "Valves {}, and {last} are open, releasing {pressure} pressure.",
+                    rest.join(", ")
+                ),
+            };
+            writeln!(explanation, "== Minute {minute} ==\n{valves}").unwrap();
+            for action in actions {
+                writeln!(explanation, "{action}").unwrap();
+            }
+            explanation.push('\n');
+        }
+        explanation + &format!("Total pressure released: {}", plan.released)
+    }
+}
+
+/// Run the valve optimizer on a custom scenario, printing the best plan.
+/// Uses the puzzle input unless another input file is given.
+pub fn run_scenario(
+    input: Option<&Path>,
+    default_input: &'static str,
+    scenario: &Scenario,
+    method: Method,
+) -> Result<(), String> {
+    let input = match input {
+        Some(path) => {
+            let input = std::fs::read_to_string(path)
+                .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+            &*Box::leak(input.into_boxed_str())
+        }
+        None => default_input,
+    };
+    let plan = Day16::parse(input).plan(scenario, method)?;
+    println!("{plan}");
+    Ok(())
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct State<const TURNS_PER_AGENT: usize> {
+struct State {
     total_released: usize,
     closed_valves: Vec<ValveIdx>,
     location: ValveIdx,
     turns_remaining: usize,
-    agents_remaining: usize,
+    /// The index of the agent currently moving. Agents move one after
+    /// another, each starting once the previous one is finished.
+    agent: usize,
 }
 
-impl<const TURNS_PER_AGENT: usize> PartialOrd for State<TURNS_PER_AGENT> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.total_released.partial_cmp(&other.total_released)
-    }
+/// Maximising the pressure released in a scenario.
+struct Release<'a> {
+    network: &'a Network,
+    /// Each agent's starting valve and minutes.
+    agents: Vec<(ValveIdx, usize)>,
+    opening_cost: usize,
+    /// The latest minute any agent finishes at.
+    minutes: usize,
 }
 
-impl<const TURNS_PER_AGENT: usize> Ord for State<TURNS_PER_AGENT> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.total_released.cmp(&other.total_released)
+impl<'a> Release<'a> {
+    fn new(network: &'a Network, scenario: &Scenario) -> Result<Self, String> {
+        if scenario.agents.is_empty() {
+            return Err("there must be at least one agent".to_string());
+        }
+        if network.openable > 64 {
+            return Err(format!("too many valves with flow: {}", network.openable));
+        }
+        let agents = scenario
+            .agents
+            .iter()
+            .map(|agent| (network.index_of(&agent.start), agent.minutes))
+            .collect();
+        Ok(Self {
+            network,
+            agents,
+            opening_cost: scenario.opening_cost,
+            minutes: scenario.minutes(),
+        })
     }
-}
 
-impl<const TURNS_PER_AGENT: usize> State<TURNS_PER_AGENT> {
-    fn new(valves: &Day16, agents: usize) -> Self {
-        let mut closed_valves = Vec::new();
-        for valve in 0..valves.valves.len() {
-            closed_valves.push(ValveIdx(valve));
-        }
-        Self {
+    /// The state before the agents in `agents` move, with only the valves in
+    /// `closed` left to open.
+    fn start(&self, first_agent: usize, closed: u64) -> State {
+        let (location, turns_remaining) = self.agents[first_agent];
+        State {
             total_released: 0,
-            closed_valves,
-            location: valves.starting_valve,
-            turns_remaining: TURNS_PER_AGENT,
-            agents_remaining: agents - 1,
+            closed_valves: (0..self.network.openable)
+                .filter(|valve| closed & 1 << valve != 0)
+                .map(ValveIdx)
+                .collect(),
+            location,
+            turns_remaining,
+            agent: first_agent,
         }
     }
 
-    fn upper_bound(&self, valves: &Day16) -> usize {
-        self.total_released
-            + self
-                .closed_valves
-                .iter()
-                .map(|&valve| {
-                    valves.valve(valve).flow
-                        * self
-                            .turns_remaining
-                            .saturating_sub(valves.valve(self.location).distance_to(valve))
-                            .max(if self.agents_remaining > 0 {
-                                TURNS_PER_AGENT.saturating_sub(
-                                    valves.valve(valves.starting_valve).distance_to(valve),
-                                )
-                            } else {
-                                0
-                            })
-                })
-                .sum::<usize>()
+    fn best(&self) -> usize {
+        branch_and_bound(&Search {
+            release: self,
+            start: self.start(0, u64::MAX),
+            last_agent: self.agents.len() - 1,
+        })
+        .value
+    }
+
+    /// Search for the best plan which only opens valves in `closed`.
+    fn plan(&self, closed: u64) -> Plan {
+        self.plan_agents(0..self.agents.len(), closed)
+    }
+
+    fn plan_agents(&self, agents: std::ops::Range<usize>, closed: u64) -> Plan {
+        let (solution, path) = branch_and_bound_path(&Search {
+            release: self,
+            start: self.start(agents.start, closed),
+            last_agent: agents.end - 1,
+        });
+        let openings = path
+            .windows(2)
+            .filter(|pair| pair[0].agent == pair[1].agent)
+            .map(|pair| Opening {
+                agent: pair[1].agent,
+                valve: self.network.names[pair[1].location.0],
+                minute: self.minutes - pair[1].turns_remaining,
+            })
+            .collect();
+        Plan {
+            released: solution.value,
+            openings,
+        }
+    }
+
+    /// Give each agent a disjoint subset of the valves, using the best
+    /// release for each subset by each agent alone. Returns the best release
+    /// and each agent's subset.
+    fn best_subsets(&self) -> Result<(usize, Vec<usize>), String> {
+        if self.network.openable > 16 {
+            return Err(format!(
+                "too many valves with flow to try every subset: {}",
+                self.network.openable
+            ));
+        }
+        let tables: Vec<_> = self
+            .agents
+            .iter()
+            .map(|&(start, minutes)| {
+                self.network
+                    .subset_releases(start, minutes, self.opening_cost)
+            })
+            .collect();
+        // The best release by the agents so far for every set of valves, and
+        // the subset given to the latest agent for each.
+        let mut combined = tables[0].clone();
+        let mut choices = Vec::new();
+        let all = (1 << self.network.openable) - 1;
+        for table in &tables[1..] {
+            let (next, choice): (Vec<_>, Vec<_>) = if choices.len() + 2 == tables.len() {
+                // The last agent only needs the split of every valve.
+                let mut next = vec![0; all + 1];
+                let mut choice = vec![0; all + 1];
+                (next[all], choice[all]) = best_split(table, &combined, all);
+                (next, choice)
+            } else {
+                (0..=all)
+                    .map(|mask| best_split(table, &combined, mask))
+                    .unzip()
+            };
+            combined = next;
+            choices.push(choice);
+        }
+
+        let mut subsets = vec![0; self.agents.len()];
+        let mut mask = all;
+        for (agent, choice) in choices.iter().enumerate().rev() {
+            subsets[agent + 1] = choice[mask];
+            mask ^= choice[mask];
+        }
+        subsets[0] = mask;
+        Ok((combined[all], subsets))
+    }
+
+    /// Plan each agent's route through their subset of the valves.
+    fn plan_by_subsets(&self) -> Result<Plan, String> {
+        let (released, subsets) = self.best_subsets()?;
+        let mut plan = Plan {
+            released: 0,
+            openings: Vec::new(),
+        };
+        for (agent, subset) in subsets.into_iter().enumerate() {
+            let agent_plan = self.plan_agents(agent..agent + 1, subset as u64);
+            plan.released += agent_plan.released;
+            plan.openings.extend(agent_plan.openings);
+        }
+        debug_assert_eq!(plan.released, released);
+        Ok(plan)
     }
+}
+
+/// Branch and bound over the routes of the agents from `start`'s agent to
+/// `last_agent`.
+struct Search<'a> {
+    release: &'a Release<'a>,
+    start: State,
+    last_agent: usize,
+}
+
+impl Search<'_> {
+    /// The pressure released by opening `valve` with `turns_remaining` left
+    /// after moving from `from`, if there's time.
+    fn open(&self, from: ValveIdx, valve: ValveIdx, turns_remaining: usize) -> Option<usize> {
+        let network = self.release.network;
+        turns_remaining
+            .checked_sub(network.valve(from).distance_to(valve) + self.release.opening_cost)
+    }
+}
 
-    fn continuations<'a>(&'a self, valves: &'a Day16) -> impl Iterator<Item = Self> + 'a {
-        self.closed_valves
+impl BranchAndBound for Search<'_> {
+    type Node = State;
+    /// The location, agent and set of closed valves.
+    type Key = (ValveIdx, usize, u64);
+
+    fn root(&self) -> State {
+        self.start.clone()
+    }
+
+    fn children(&self, state: &State) -> Vec<State> {
+        let network = self.release.network;
+        let mut children: Vec<State> = state
+            .closed_valves
             .iter()
             .enumerate()
             .filter_map(|(idx, &valve)| {
-                self.turns_remaining
-                    .checked_sub(valves.valve(self.location).distance_to(valve))
+                self.open(state.location, valve, state.turns_remaining)
                     .map(|turns_remaining| {
-                        let mut closed_valves = self.closed_valves.clone();
+                        let mut closed_valves = state.closed_valves.clone();
                         closed_valves.swap_remove(idx);
-                        Self {
-                            total_released: self.total_released
-                                + valves.valve(valve).flow * turns_remaining,
+                        State {
+                            total_released: state.total_released
+                                + network.valve(valve).flow * turns_remaining,
                             closed_valves,
                             location: valve,
                             turns_remaining,
-                            ..*self
+                            ..*state
                         }
                     })
             })
-            .chain(if self.agents_remaining > 0 {
-                Some(Self {
-                    location: valves.starting_valve,
-                    turns_remaining: TURNS_PER_AGENT,
-                    agents_remaining: self.agents_remaining - 1,
-                    ..self.clone()
+            .collect();
+        if state.agent < self.last_agent {
+            let (location, turns_remaining) = self.release.agents[state.agent + 1];
+            children.push(State {
+                location,
+                turns_remaining,
+                agent: state.agent + 1,
+                ..state.clone()
+            });
+        }
+        children
+    }
+
+    fn lower_bound(&self, state: &State) -> usize {
+        state.total_released
+    }
+
+    fn upper_bound(&self, state: &State) -> usize {
+        let later_agents = &self.release.agents[state.agent + 1..=self.last_agent];
+        state.total_released
+            + state
+                .closed_valves
+                .iter()
+                .map(|&valve| {
+                    let turns = later_agents
+                        .iter()
+                        .filter_map(|&(start, minutes)| self.open(start, valve, minutes))
+                        .chain(self.open(state.location, valve, state.turns_remaining))
+                        .max()
+                        .unwrap_or(0);
+                    self.release.network.valve(valve).flow * turns
                 })
-            } else {
-                None
-            })
+                .sum::<usize>()
     }
 
-    fn max_release(self, valves: &Day16) -> usize {
-        let mut queue = BinaryHeap::new();
-        queue.push(self);
-        let mut max_release = 0;
-        while let Some(state) = queue.pop() {
-            max_release = max_release.max(state.total_released);
-            for continuation in state.continuations(valves) {
-                if continuation.upper_bound(valves) > max_release {
-                    queue.push(continuation);
-                }
-            }
-        }
-        max_release
+    fn key(&self, state: &State) -> Option<Self::Key> {
+        let closed = state
+            .closed_valves
+            .iter()
+            .fold(0, |set, valve| set | 1 << valve.0);
+        Some((state.location, state.agent, closed))
+    }
+
+    fn dominates(&self, state: &State, other: &State) -> bool {
+        state.total_released >= other.total_released
+            && state.turns_remaining >= other.turns_remaining
+    }
+}
+
+impl Explain for Day16 {
+    fn explain_part1(&self) -> String {
+        self.explain(&Scenario::puzzle(1, 30))
+    }
+
+    fn explain_part2(&self) -> String {
+        self.explain(&Scenario::puzzle(2, 26))
     }
 }
 
 impl Day for Day16 {
     fn parse(input: &'static str) -> Self {
-        RawSystem::parse(input).try_into().unwrap()
+        let raw = RawSystem::parse(input);
+        let network = Network::new(&raw, &[START]).unwrap();
+        Self { raw, network }
     }
 
     fn part1(&self) -> String {
-        State::<30>::new(self, 1).max_release(self).to_string()
+        let scenario = Scenario::puzzle(1, 30);
+        self.max_release(&scenario, Method::Search)
+            .unwrap()
+            .to_string()
     }
 
     fn part2(&self) -> String {
-        State::<26>::new(self, 2).max_release(self).to_string()
+        let scenario = Scenario::puzzle(2, 26);
+        // Splitting subsets is much faster, but only works with up to 16
+        // valves with flow.
+        self.max_release(&scenario, Method::Subsets)
+            .or_else(|_| self.max_release(&scenario, Method::Search))
+            .unwrap()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+    fn agent(start: &str, minutes: usize) -> Agent {
+        Agent {
+            start: start.to_string(),
+            minutes,
+        }
+    }
+
+    /// The best release, checking both methods agree on it.
+    fn max_release(day: &Day16, scenario: &Scenario) -> usize {
+        let search = day.max_release(scenario, Method::Search).unwrap();
+        let subsets = day.max_release(scenario, Method::Subsets).unwrap();
+        assert_eq!(search, subsets, "methods disagree on {scenario:?}");
+        let plan = day.plan(scenario, Method::Subsets).unwrap();
+        assert_eq!(plan.released, search);
+        search
+    }
+
+    #[test]
+    fn test_example() {
+        let day = Day16::parse(EXAMPLE);
+        assert_eq!(day.part1(), "1651");
+        assert_eq!(day.part2(), "1707");
+        assert_eq!(max_release(&day, &Scenario::puzzle(1, 30)), 1651);
+        assert_eq!(max_release(&day, &Scenario::puzzle(2, 26)), 1707);
+    }
+
+    #[test]
+    fn test_scenarios() {
+        let day = Day16::parse(EXAMPLE);
+        let two = max_release(&day, &Scenario::puzzle(2, 26));
+        let three = max_release(&day, &Scenario::puzzle(3, 26));
+        assert!(three >= two);
+
+        // An agent with no time does nothing, and one with less time helps
+        // less than one with the full budget.
+        let scenario = |agents| Scenario {
+            agents,
+            opening_cost: 1,
+        };
+        let idle = max_release(&day, &scenario(vec![agent("AA", 30), agent("AA", 0)]));
+        assert_eq!(idle, 1651);
+        let late = max_release(&day, &scenario(vec![agent("AA", 26), agent("JJ", 20)]));
+        assert!(late < two);
+
+        let slow = Scenario {
+            opening_cost: 2,
+            ..Scenario::puzzle(1, 30)
+        };
+        assert!(max_release(&day, &slow) < 1651);
+        let free = Scenario {
+            opening_cost: 0,
+            ..Scenario::puzzle(1, 30)
+        };
+        assert!(max_release(&day, &free) > 1651);
+    }
+
+    #[test]
+    fn test_many_valves() {
+        // A corridor from AA with valves of flow 10, 20 and 30 just along it,
+        // and another 14 with flow too far along to reach. That is too many
+        // valves with flow to try every subset.
+        let names: Vec<_> = (0..60_u8)
+            .map(|index| {
+                format!(
+                    "{}{}",
+                    (b'A' + index / 26) as char,
+                    (b'A' + index % 26) as char
+                )
+            })
+            .collect();
+        let mut input = String::new();
+        for (index, name) in names.iter().enumerate() {
+            let flow = match index {
+                1..=3 => index * 10,
+                46.. => 100,
+                _ => 0,
+            };
+            let tunnels: Vec<_> = [index.checked_sub(1), Some(index + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|other| names.get(other).map(String::as_str))
+                .collect();
+            writeln!(
+                input,
+                "Valve {name} has flow rate={flow}; tunnels lead to valves {}",
+                tunnels.join(", ")
+            )
+            .unwrap();
+        }
+        let day = Day16::parse(Box::leak(input.into_boxed_str()));
+        assert!(day
+            .max_release(&Scenario::puzzle(2, 26), Method::Subsets)
+            .is_err());
+        // One agent opens the first two valves, and the other the third.
+        assert_eq!(day.part2(), (10 * 24 + 20 * 22 + 30 * 22).to_string());
     }
 }
@@ -1,6 +1,13 @@
-use rustc_hash::{FxHashMap, FxHashSet};
+use std::path::Path;
 
-use crate::{vec2::Vec2, Day};
+use crate::{
+    cycle::{Cycle, CycleDetector},
+    grid::Grid,
+    pointset::{DenseSet, PointSet, SparseSet},
+    vec2::Vec2,
+    visualize::{Recorder, Visualize},
+    Day,
+};
 
 #[derive(Clone)]
 pub struct Day17(Vec<Jet>);
@@ -20,6 +27,17 @@ impl Jet {
     }
 }
 
+fn parse_jets(input: &str) -> Vec<Jet> {
+    input
+        .chars()
+        .map(|c| match c {
+            '<' => Jet::Left,
+            '>' => Jet::Right,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 struct Block {
     offsets: Vec<Vec2>,
@@ -31,51 +49,38 @@ struct Block {
 }
 
 impl Block {
-    fn new(offsets: Vec<Vec2>) -> Self {
-        let height = offsets.iter().map(|v| v.1).max().unwrap() + 1;
-        let width = offsets.iter().map(|v| v.0).max().unwrap() + 1;
-        let lower_edge = (0..width)
-            .map(|x| {
-                offsets
-                    .iter()
-                    .filter(|v| v.0 == x)
-                    .min_by_key(|v| v.1)
-                    .unwrap()
-            })
-            .copied()
-            .collect();
-        let left_edge = (0..height)
-            .map(|y| {
-                offsets
-                    .iter()
-                    .filter(|v| v.1 == y)
-                    .min_by_key(|v| v.0)
-                    .unwrap()
-            })
-            .copied()
-            .collect();
-        let right_edge = (0..height)
-            .map(|y| {
-                offsets
-                    .iter()
-                    .filter(|v| v.1 == y)
-                    .max_by_key(|v| v.0)
-                    .unwrap()
-            })
-            .copied()
-            .collect();
+    /// A block made of the cells at these offsets, moved so that its lowest
+    /// and leftmost cells are at zero.
+    fn new(offsets: &[Vec2]) -> Self {
+        let (min, max) = Vec2::bounding_box(offsets.iter().copied()).unwrap();
+        let offsets: Vec<Vec2> = offsets.iter().map(|&offset| offset - min).collect();
+        // The cells which could run into something when the block moves in a
+        // direction, because the block doesn't already cover the next cell.
+        let edge = |direction: Vec2| -> Vec<Vec2> {
+            offsets
+                .iter()
+                .copied()
+                .filter(|&offset| !offsets.contains(&(offset + direction)))
+                .collect()
+        };
+        let lower_edge = edge(DOWN);
+        let left_edge = edge(Jet::Left.as_delta());
+        let right_edge = edge(Jet::Right.as_delta());
         Self {
             offsets,
-            height,
-            width,
+            height: max.y() - min.y() + 1,
+            width: max.x() - min.x() + 1,
             lower_edge,
             left_edge,
             right_edge,
         }
     }
 
-    fn from_raw(raw: &str) -> Self {
-        let offsets = raw
+    fn from_raw(raw: &str) -> Result<Self, String> {
+        if let Some(c) = raw.chars().find(|c| !matches!(c, '#' | '.' | '\n')) {
+            return Err(format!("invalid character {c:?} in block"));
+        }
+        let offsets: Vec<_> = raw
             .lines()
             .rev()
             .enumerate()
@@ -83,10 +88,21 @@ impl Block {
             .filter(|(_, _, c)| *c == '#')
             .map(|(x, y, _)| Vec2::new(x as isize, y as isize))
             .collect();
-        Self::new(offsets)
+        if offsets.is_empty() {
+            return Err("block has no rock in it".to_string());
+        }
+        Ok(Self::new(&offsets))
     }
 }
 
+/// Parse blocks in the same format as [`RAW_BLOCKS`], separated by blank lines.
+fn parse_blocks(raw: &str) -> Result<Vec<Block>, String> {
+    raw.trim_matches('\n')
+        .split("\n\n")
+        .map(Block::from_raw)
+        .collect()
+}
+
 const RAW_BLOCKS: &str = "####
 
 .#.
@@ -106,25 +122,28 @@ const RAW_BLOCKS: &str = "####
 ##";
 
 lazy_static::lazy_static! {
-    static ref BLOCKS: Vec<Block> = RAW_BLOCKS.split("\n\n").map(Block::from_raw).collect();
+    static ref BLOCKS: Vec<Block> = parse_blocks(RAW_BLOCKS).unwrap();
 }
 
-const WIDTH: isize = 7;
+/// The width of the puzzle's trench.
+pub const WIDTH: isize = 7;
 const DOWN: Vec2 = Vec2::new(0, -1);
 
 struct Trench<'a> {
     jets: &'a [Jet],
-    cells: FxHashSet<Vec2>,
+    width: isize,
+    cells: SparseSet,
     height: isize,
     buried_height: isize,
     jet_index: usize,
 }
 
 impl<'a> Trench<'a> {
-    fn new(jets: &'a [Jet]) -> Self {
+    fn new(jets: &'a [Jet], width: isize) -> Self {
         Trench {
             jets,
-            cells: FxHashSet::default(),
+            width,
+            cells: SparseSet::default(),
             height: 0,
             buried_height: 0,
             jet_index: 0,
@@ -147,12 +166,12 @@ impl<'a> Trench<'a> {
 
     fn fall(&mut self, block: &Block) {
         let mut position = Vec2::new(2, self.height);
-        let max_x = WIDTH - block.width;
+        let max_x = self.width - block.width;
         for _ in 0..4 {
             position += self.next_jet().as_delta();
-            position.0 = position.0.clamp(0, max_x);
+            position[0] = position.x().clamp(0, max_x);
         }
-        while position.1 > 0 && self.is_clear(position + DOWN, &block.lower_edge) {
+        while position.y() > 0 && self.is_clear(position + DOWN, &block.lower_edge) {
             position += DOWN;
             let jet = self.next_jet();
             let edge = match jet {
@@ -162,36 +181,36 @@ impl<'a> Trench<'a> {
             let new_position = position + jet.as_delta();
             if self.is_clear(new_position, edge) {
                 position = new_position;
-                position.0 = position.0.clamp(0, max_x);
+                position[0] = position.x().clamp(0, max_x);
             }
         }
         for &offset in &block.offsets {
             self.cells.insert(position + offset);
         }
-        self.height = self.height.max(block.height + position.1);
+        self.height = self.height.max(block.height + position.y());
     }
 
     fn is_clear(&self, position: Vec2, offsets: &[Vec2]) -> bool {
         offsets
             .iter()
-            .all(|&offset| !self.cells.contains(&(position + offset)))
+            .all(|&offset| !self.cells.contains(position + offset))
     }
 
     fn clear_buried(&mut self) {
-        let mut visited = FxHashSet::default();
+        let mut visited = DenseSet::new(Vec2::ZERO, Vec2::new(self.width - 1, self.height - 1));
         let mut open: Vec<Vec2> = Vec::new();
-        for x in 0..WIDTH {
+        for x in 0..self.width {
             open.push(Vec2::new(x, self.height - 1));
         }
-        let mut cells = FxHashSet::default();
+        let mut cells = SparseSet::default();
         while let Some(next) = open.pop() {
-            if next.0 < 0 || next.0 >= WIDTH || next.1 < 0 || next.1 >= self.height {
+            if next.x() < 0 || next.x() >= self.width || next.y() < 0 || next.y() >= self.height {
                 continue;
             }
             if !visited.insert(next) {
                 continue;
             }
-            if self.cells.contains(&next) {
+            if self.cells.contains(next) {
                 cells.insert(next);
             } else {
                 open.push(next + Vec2::new(-1, 0));
@@ -202,32 +221,53 @@ impl<'a> Trench<'a> {
         if cells.is_empty() {
             return;
         }
-        let height_buried = cells.iter().map(|v| v.1).min().unwrap();
+        let height_buried = cells.iter().map(|v| v.y()).min().unwrap();
         self.cells = cells
             .iter()
-            .map(|&v| v - Vec2::new(0, height_buried))
+            .map(|v| v - Vec2::new(0, height_buried))
             .collect();
         self.buried_height += height_buried;
         self.height -= height_buried;
     }
 }
 
+impl Trench<'_> {
+    /// Draw the top `rows` rows of the trench (including the space above the
+    /// highest rock where blocks appear) between its walls.
+    fn draw(&self, rows: usize) -> Grid<char> {
+        let top = self.height + 3;
+        Grid::from_fn(self.width as usize + 2, rows, |pos| {
+            let (x, y) = (pos.x() - 1, top - pos.y());
+            if y < 0 {
+                '-'
+            } else if !(0..self.width).contains(&x) {
+                '|'
+            } else if self.cells.contains(Vec2::new(x, y)) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+    }
+}
+
+impl Visualize for Day17 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        let mut trench = Trench::new(&self.0, WIDTH);
+        for block in BLOCKS.iter().cycle().take(200) {
+            trench.fall(block);
+            recorder.record(trench.draw(40));
+        }
+    }
+}
+
 impl Day for Day17 {
     fn parse(input: &str) -> Self {
-        Self(
-            input
-                .chars()
-                .map(|c| match c {
-                    '<' => Jet::Left,
-                    '>' => Jet::Right,
-                    _ => unreachable!(),
-                })
-                .collect(),
-        )
+        Self(parse_jets(input))
     }
 
     fn part1(&self) -> String {
-        let mut trench = Trench::new(&self.0);
+        let mut trench = Trench::new(&self.0, WIDTH);
         for block in BLOCKS.iter().cycle().take(2022) {
             trench.fall(block);
         }
@@ -235,33 +275,269 @@ impl Day for Day17 {
     }
 
     fn part2(&self) -> String {
-        let mut trench = Trench::new(&self.0);
-        let mut visited = FxHashMap::default();
-        let mut remaining = 1_000_000_000_000;
-        while remaining >= BLOCKS.len() as isize {
-            for block in BLOCKS.iter() {
-                trench.fall(block);
+        tower_height(&self.0, &BLOCKS, WIDTH, 1_000_000_000_000).to_string()
+    }
+}
+
+/// The height of the tower after `count` blocks have fallen into a trench,
+/// taking each of `blocks` in turn.
+fn tower_height(jets: &[Jet], blocks: &[Block], width: isize, count: usize) -> isize {
+    // Each step drops one of each block, so the trench's state only depends
+    // on the jet index and the shape of its surface.
+    let mut trench = Trench::new(jets, width);
+    let mut detector = CycleDetector::new();
+    detector.record((trench.jet_index, Vec::new()), trench.full_height());
+    let mut heights = vec![trench.full_height()];
+    let mut cycle = None;
+    while cycle.is_none() && heights.len() <= count {
+        for block in blocks {
+            trench.fall(block);
+            heights.push(trench.full_height());
+        }
+        trench.clear_buried();
+        let mut key: Vec<_> = trench.cells.iter().collect();
+        key.sort();
+        cycle = detector.record((trench.jet_index, key), trench.full_height());
+    }
+    match cycle {
+        // The heights after each block repeat along with the steps.
+        Some(Cycle { start, period }) => Cycle {
+            start: start * blocks.len(),
+            period: period * blocks.len(),
+        }
+        .extrapolate(&heights, count),
+        None => heights[count],
+    }
+}
+
+/// Drop blocks into a trench with custom jets, blocks or width, and print the
+/// height of the tower.
+pub fn run_blocks(
+    input: Option<&Path>,
+    default_input: &str,
+    blocks: Option<&Path>,
+    width: usize,
+    count: usize,
+) -> Result<(), String> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {err}", path.display()))
+    };
+    let input = input.map_or_else(|| Ok(default_input.to_string()), read)?;
+    let input = input.trim();
+    if let Some(c) = input.chars().find(|c| !matches!(c, '<' | '>')) {
+        return Err(format!("invalid jet {c:?}"));
+    }
+    if input.is_empty() {
+        return Err("there are no jets".to_string());
+    }
+    let blocks = match blocks {
+        Some(path) => parse_blocks(&read(path)?)?,
+        None => parse_blocks(RAW_BLOCKS)?,
+    };
+    let width = width as isize;
+    // Blocks appear with their left edge two units from the left wall.
+    if let Some(block) = blocks.iter().find(|block| block.width + 2 > width) {
+        return Err(format!(
+            "a block {} wide doesn't fit two units from the left wall of a trench {width} wide",
+            block.width
+        ));
+    }
+    let jets = parse_jets(input);
+    // A bitmask per row is much faster, if a row fits in one.
+    if width as usize <= DenseTrench::MAX_WIDTH {
+        println!(
+            "{}",
+            DenseTrench::new(&jets, &blocks, width as usize).height_after(count)
+        );
+    } else {
+        println!("{}", tower_height(&jets, &blocks, width, count));
+    }
+    Ok(())
+}
+
+/// A block as a bitmask per row from the bottom up, with bit `x` set for
+/// column `x` counting from the left.
+#[derive(Clone, Debug)]
+struct Shape {
+    rows: Vec<u8>,
+    width: usize,
+}
+
+impl From<&Block> for Shape {
+    fn from(block: &Block) -> Self {
+        let mut rows = vec![0; block.height as usize];
+        for offset in &block.offsets {
+            rows[offset.y() as usize] |= 1 << offset.x();
+        }
+        Self {
+            rows,
+            width: block.width as usize,
+        }
+    }
+}
+
+/// The same as [`Trench`], but storing each row of the trench as a bitmask,
+/// with none above the highest rock.
+struct DenseTrench<'a> {
+    jets: &'a [Jet],
+    shapes: Vec<Shape>,
+    width: usize,
+    rows: Vec<u8>,
+    jet_index: usize,
+    shape_index: usize,
+}
+
+impl<'a> DenseTrench<'a> {
+    /// The widest trench whose rows fit in a byte.
+    const MAX_WIDTH: usize = u8::BITS as usize;
+
+    /// # Panics
+    ///
+    /// If the trench is wider than [`Self::MAX_WIDTH`].
+    fn new(jets: &'a [Jet], blocks: &[Block], width: usize) -> Self {
+        assert!(
+            width <= Self::MAX_WIDTH,
+            "a trench {width} wide needs more than a byte per row"
+        );
+        Self {
+            jets,
+            shapes: blocks.iter().map(Shape::from).collect(),
+            width,
+            rows: Vec::new(),
+            jet_index: 0,
+            shape_index: 0,
+        }
+    }
+
+    /// Whether a shape fits with its left edge in column `x` and its bottom
+    /// edge in row `y`.
+    fn fits(&self, shape: &Shape, x: usize, y: usize) -> bool {
+        x + shape.width <= self.width
+            && shape.rows.iter().enumerate().all(|(i, &row)| {
+                self.rows
+                    .get(y + i)
+                    .is_none_or(|&rocks| rocks & row << x == 0)
+            })
+    }
+
+    /// Drop the next shape.
+    fn fall(&mut self) {
+        let shape = &self.shapes[self.shape_index];
+        self.shape_index = (self.shape_index + 1) % self.shapes.len();
+        let (mut x, mut y): (usize, _) = (2, self.rows.len() + 3);
+        loop {
+            let jet = self.jets[self.jet_index];
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
+            let pushed = match jet {
+                Jet::Left => x.checked_sub(1),
+                Jet::Right => Some(x + 1),
+            };
+            if let Some(pushed) = pushed.filter(|&pushed| self.fits(shape, pushed, y)) {
+                x = pushed;
+            }
+            if y == 0 || !self.fits(shape, x, y - 1) {
+                break;
             }
-            remaining -= BLOCKS.len() as isize;
-            //    if trench.jet_index == 0 {
-            trench.clear_buried();
-            let mut key: Vec<_> = trench.cells.iter().copied().collect();
-            key.sort();
-            if let Some((remaining_then, height_then)) =
-                visited.insert((trench.jet_index, key), (remaining, trench.full_height()))
+            y -= 1;
+        }
+        let top = y + shape.rows.len();
+        if self.rows.len() < top {
+            self.rows.resize(top, 0);
+        }
+        for (i, &row) in shape.rows.iter().enumerate() {
+            self.rows[y + i] |= row << x;
+        }
+    }
+
+    /// How far down from the top each column's highest rock is, as a byte
+    /// per column. Depths past 255 are all counted as 255.
+    fn profile(&self) -> u64 {
+        (0..self.width).fold(0, |profile, x| {
+            let depth = self
+                .rows
+                .iter()
+                .rev()
+                .position(|&row| row & 1 << x != 0)
+                .unwrap_or(self.rows.len());
+            profile << 8 | depth.min(u8::MAX as usize) as u64
+        })
+    }
+
+    /// The height of the tower after dropping `count` blocks, finding a cycle
+    /// in the jet, the next shape and the surface profile after each block.
+    fn height_after(mut self, count: usize) -> usize {
+        let mut detector = CycleDetector::new();
+        detector.record((0, 0, self.profile()), 0);
+        for _ in 0..count {
+            self.fall();
+            let fingerprint = (self.shape_index, self.jet_index, self.profile());
+            if detector
+                .record(fingerprint, self.rows.len() as isize)
+                .is_some()
             {
-                let period = remaining_then - remaining;
-                let increment = trench.full_height() - height_then;
-                let cycles = remaining / period;
-                remaining %= period;
-                trench.buried_height += cycles * increment;
                 break;
-                //               }
             }
         }
-        for block in BLOCKS.iter().cycle().take(remaining as usize) {
-            trench.fall(block);
+        detector
+            .extrapolate(count)
+            .map_or(self.rows.len(), |height| height as usize)
+    }
+}
+
+/// The same as [`Day17`], but using [`DenseTrench`].
+#[derive(Clone)]
+pub struct Day17Dense(Vec<Jet>);
+
+impl Day for Day17Dense {
+    fn parse(input: &str) -> Self {
+        Self(parse_jets(input))
+    }
+
+    fn part1(&self) -> String {
+        DenseTrench::new(&self.0, &BLOCKS, WIDTH as usize)
+            .height_after(2022)
+            .to_string()
+    }
+
+    fn part2(&self) -> String {
+        DenseTrench::new(&self.0, &BLOCKS, WIDTH as usize)
+            .height_after(1_000_000_000_000)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
+    #[test]
+    fn test_example() {
+        for day in [
+            Day17::parse(EXAMPLE).part1(),
+            Day17Dense::parse(EXAMPLE).part1(),
+        ] {
+            assert_eq!(day, "3068");
+        }
+        assert_eq!(Day17Dense::parse(EXAMPLE).part2(), "1514285714288");
+    }
+
+    #[test]
+    fn test_dense_matches_sparse() {
+        let jets = parse_jets(EXAMPLE);
+        let custom = parse_blocks("#.\n##\n\n###\n\n#\n#").unwrap();
+        for blocks in [&BLOCKS[..], &custom] {
+            for width in 6..=DenseTrench::MAX_WIDTH {
+                for count in [2022, 100_000] {
+                    assert_eq!(
+                        DenseTrench::new(&jets, blocks, width).height_after(count),
+                        tower_height(&jets, blocks, width as isize, count) as usize,
+                        "{width} wide after {count} blocks"
+                    );
+                }
+            }
         }
-        trench.full_height().to_string()
     }
 }
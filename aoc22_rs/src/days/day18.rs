@@ -1,5 +1,6 @@
 use rustc_hash::FxHashSet;
 
+use crate::vec2::Vec3;
 use crate::Day;
 
 #[derive(Clone)]
@@ -10,21 +11,20 @@ struct Scan(Vec<Vec3>);
 
 impl Scan {
     fn parse(raw: &str) -> Self {
-        Self(raw.lines().map(Vec3::parse).collect())
+        Self(raw.lines().map(parse_vec3).collect())
+    }
+
+    fn cubes(&self) -> FxHashSet<Vec3> {
+        self.0.iter().copied().collect()
     }
 
     fn surface_area(&self) -> usize {
-        let mut sides = FxHashSet::default();
-        for pos in &self.0 {
-            for side in Side::all_from(*pos) {
-                if sides.contains(&side) {
-                    sides.remove(&side);
-                } else {
-                    sides.insert(side);
-                }
-            }
-        }
-        sides.len()
+        let cubes = self.cubes();
+        self.0
+            .iter()
+            .flat_map(|pos| pos.adjacents())
+            .filter(|adj| !cubes.contains(adj))
+            .count()
     }
 
     fn outer_surface_area(&self) -> usize {
@@ -50,15 +50,10 @@ impl Scan {
     }
 
     fn floodfill(&self) -> Vec<Vec3> {
-        let min = self.0.iter().copied().fold(Vec3::MAX, Vec3::piecewise_min)
-            + Vec3 {
-                x: -1,
-                y: -1,
-                z: -1,
-            };
-        let max =
-            self.0.iter().copied().fold(Vec3::MIN, Vec3::piecewise_max) + Vec3 { x: 1, y: 1, z: 1 };
-        let mut open = Vec::from(min.product(max));
+        let cubes = self.cubes();
+        let (min, max) = Vec3::bounding_box(self.0.iter().copied()).unwrap();
+        let (min, max) = (min - Vec3::new(1, 1, 1), max + Vec3::new(1, 1, 1));
+        let mut open = vec![min];
         let mut visited = FxHashSet::default();
         let mut edge = Vec::new();
         while let Some(pos) = open.pop() {
@@ -67,16 +62,12 @@ impl Scan {
             }
             let mut is_edge = false;
             for adj in pos.adjacents() {
-                let contains = self.0.contains(&adj);
+                let contains = cubes.contains(&adj);
                 if contains && !is_edge {
                     edge.push(pos);
                     is_edge = true;
                 }
-                if (min.x..=max.x).contains(&adj.x)
-                    && (min.y..=max.y).contains(&adj.y)
-                    && (min.z..=max.z).contains(&adj.z)
-                    && !contains
-                {
+                if adj.within(min, max) && !contains {
                     open.push(adj);
                 }
             }
@@ -85,87 +76,9 @@ impl Scan {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
-struct Vec3 {
-    x: i8,
-    y: i8,
-    z: i8,
-}
-
-impl Vec3 {
-    const MIN: Self = Self {
-        x: -128,
-        y: -128,
-        z: -128,
-    };
-    const MAX: Self = Self {
-        x: 127,
-        y: 127,
-        z: 127,
-    };
-
-    fn parse(raw: &str) -> Self {
-        let mut raw_parts = raw.splitn(3, ',');
-        Self {
-            x: raw_parts.next().unwrap().parse().unwrap(),
-            y: raw_parts.next().unwrap().parse().unwrap(),
-            z: raw_parts.next().unwrap().parse().unwrap(),
-        }
-    }
-
-    fn piecewise_min(self, rhs: Self) -> Self {
-        Self {
-            x: self.x.min(rhs.x),
-            y: self.y.min(rhs.y),
-            z: self.z.min(rhs.z),
-        }
-    }
-
-    fn piecewise_max(self, rhs: Self) -> Self {
-        Self {
-            x: self.x.max(rhs.x),
-            y: self.y.max(rhs.y),
-            z: self.z.max(rhs.z),
-        }
-    }
-
-    const fn product(self, other: Self) -> [Self; 8] {
-        [
-            self,
-            other,
-            Self { x: self.x, ..other },
-            Self { y: self.y, ..other },
-            Self { z: self.z, ..other },
-            Self { x: other.x, ..self },
-            Self { y: other.y, ..self },
-            Self { z: other.z, ..self },
-        ]
-    }
-
-    fn adjacents(self) -> [Self; 6] {
-        CARDINALS.map(|cardinal| self + cardinal)
-    }
-}
-
-const CARDINALS: [Vec3; 6] = [
-    Vec3 { x: 1, y: 0, z: 0 },
-    Vec3 { x: -1, y: 0, z: 0 },
-    Vec3 { x: 0, y: 1, z: 0 },
-    Vec3 { x: 0, y: -1, z: 0 },
-    Vec3 { x: 0, y: 0, z: 1 },
-    Vec3 { x: 0, y: 0, z: -1 },
-];
-
-impl std::ops::Add for Vec3 {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
+fn parse_vec3(raw: &str) -> Vec3 {
+    let mut raw_parts = raw.splitn(3, ',').map(|part| part.parse().unwrap());
+    Vec3::from_coords(std::array::from_fn(|_| raw_parts.next().unwrap()))
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -190,24 +103,15 @@ impl Side {
                 plane: Plane::YZ,
             },
             Self {
-                pos: Vec3 {
-                    x: pos.x + 1,
-                    ..pos
-                },
+                pos: Vec3::new(pos.x() + 1, pos.y(), pos.z()),
                 plane: Plane::YZ,
             },
             Self {
-                pos: Vec3 {
-                    y: pos.y + 1,
-                    ..pos
-                },
+                pos: Vec3::new(pos.x(), pos.y() + 1, pos.z()),
                 plane: Plane::XZ,
             },
             Self {
-                pos: Vec3 {
-                    z: pos.z + 1,
-                    ..pos
-                },
+                pos: Vec3::new(pos.x(), pos.y(), pos.z() + 1),
                 plane: Plane::XY,
             },
         ]
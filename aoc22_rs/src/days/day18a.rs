@@ -1,3 +1,4 @@
+use crate::vec2::Vec3;
 use crate::Day;
 
 #[derive(Clone)]
@@ -8,7 +9,7 @@ struct Scan(Vec<Vec3>);
 
 impl Scan {
     fn parse(raw: &str) -> Self {
-        Self(raw.lines().map(Vec3::parse).collect())
+        Self(raw.lines().map(parse_vec3).collect())
     }
 
     fn surface_area(&self) -> usize {
@@ -33,9 +34,9 @@ impl Scan {
     }
 
     fn floodfill(&self) -> Vec<Vec3> {
-        let min = self.0.iter().copied().fold(Vec3::MAX, Vec3::piecewise_min) + Vec3 { x: -1, y: -1, z: -1 };
-        let max = self.0.iter().copied().fold(Vec3::MIN, Vec3::piecewise_max) + Vec3 { x: 1, y: 1, z: 1 };
-        let mut open = Vec::from(min.product(max));
+        let (min, max) = Vec3::bounding_box(self.0.iter().copied()).unwrap();
+        let (min, max) = (min - Vec3::new(1, 1, 1), max + Vec3::new(1, 1, 1));
+        let mut open = Vec::from(corners(min, max));
         let mut visited = PointSet::new();
         let mut edge = Vec::new();
         while let Some(pos) = open.pop() {
@@ -51,7 +52,7 @@ impl Scan {
                     is_edge = true;
                 }
                 println!("{min:?} {max:?} {adj:?}");
-                if (min.x..=max.x).contains(&adj.x) && (min.y..=max.y).contains(&adj.y) && (min.z..=max.z).contains(&adj.z) && !contains {
+                if adj.within(min, max) && !contains {
                     open.push(adj);
                 }
             }
@@ -60,6 +61,24 @@ impl Scan {
     }
 }
 
+fn parse_vec3(raw: &str) -> Vec3 {
+    let mut raw_parts = raw.splitn(3, ',').map(|part| part.parse().unwrap());
+    Vec3::from_coords(std::array::from_fn(|_| raw_parts.next().unwrap()))
+}
+
+/// The eight corners of the box between `min` and `max`.
+fn corners(min: Vec3, max: Vec3) -> [Vec3; 8] {
+    std::array::from_fn(|index| {
+        Vec3::from_coords(std::array::from_fn(|axis| {
+            if index >> axis & 1 == 0 {
+                min[axis]
+            } else {
+                max[axis]
+            }
+        }))
+    })
+}
+
 struct PointSet([u32; 462]);
 
 impl PointSet {
@@ -68,11 +87,11 @@ impl PointSet {
     }
 
     fn pos_index(pos: Vec3) -> usize {
-        ((pos.x + 1) as usize) * 21 + (pos.y + 1) as usize
+        ((pos.x() + 1) as usize) * 21 + (pos.y() + 1) as usize
     }
 
     fn pos_mask(pos: Vec3) -> u32 {
-        1 << ((pos.z + 1) as u32)
+        1 << ((pos.z() + 1) as u32)
     }
 
     fn set(&mut self, pos: Vec3) {
@@ -88,88 +107,11 @@ impl PointSet {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-struct Vec3 {
-    x: i8,
-    y: i8,
-    z: i8,
-}
-
-impl Vec3 {
-    const MIN: Vec3 = Vec3 { x: -128, y: -128, z: -128 };
-    const MAX: Vec3 = Vec3 { x: 127, y: 127, z: 127 };
-
-    fn parse(raw: &str) -> Self {
-        let mut raw_parts = raw.splitn(3, ',');
-        Self {
-            x: raw_parts.next().unwrap().parse().unwrap(),
-            y: raw_parts.next().unwrap().parse().unwrap(),
-            z: raw_parts.next().unwrap().parse().unwrap(),
-        }
-    }
-
-    fn piecewise_min(self, rhs: Self) -> Self {
-        Self {
-            x: self.x.min(rhs.x),
-            y: self.y.min(rhs.y),
-            z: self.z.min(rhs.z),
-        }
-    }
-
-    fn piecewise_max(self, rhs: Self) -> Self {
-        Self {
-            x: self.x.max(rhs.x),
-            y: self.y.max(rhs.y),
-            z: self.z.max(rhs.z),
-        }
-    }
-
-    fn product(self, other: Self) -> [Self; 8] {
-        [
-            self,
-            other,
-            Self { x: self.x, ..other },
-            Self { y: self.y, ..other },
-            Self { z: self.z, ..other },
-            Self { x: other.x, ..self },
-            Self { y: other.y, ..self },
-            Self { z: other.z, ..self },
-        ]
-    }
-
-    fn adjacents(self) -> [Self; 6] {
-        CARDINALS.map(|&cardinal| self + cardinal)
-    }
-}
-
-const CARDINALS: [Vec3; 6] = [
-    Vec3 { x: 1, y: 0, z: 0 },
-    Vec3 { x: -1, y: 0, z: 0 },
-    Vec3 { x: 0, y: 1, z: 0 },
-    Vec3 { x: 0, y: -1, z: 0 },
-    Vec3 { x: 0, y: 0, z: 1 },
-    Vec3 { x: 0, y: 0, z: -1 },
-];
-
-impl std::ops::Add for Vec3 {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
-
 struct SideSet([u64; 462]);
 
 #[derive(Debug)]
 struct Side {
-    x: i8,
-    y: i8,
-    z: i8,
+    pos: Vec3,
     plane: Plane,
 }
 
@@ -177,39 +119,27 @@ impl Side {
     fn all_from(pos: Vec3) -> [Self; 6] {
         [
             Self {
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
+                pos,
                 plane: Plane::XY,
             },
             Self {
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
+                pos,
                 plane: Plane::XZ,
             },
             Self {
-                x: pos.x,
-                y: pos.y,
-                z: pos.z,
+                pos,
                 plane: Plane::YZ,
             },
             Self {
-                x: pos.x + 1,
-                y: pos.y,
-                z: pos.z,
+                pos: pos + Vec3::new(1, 0, 0),
                 plane: Plane::YZ,
             },
             Self {
-                x: pos.x,
-                y: pos.y + 1,
-                z: pos.z,
+                pos: pos + Vec3::new(0, 1, 0),
                 plane: Plane::XZ,
             },
             Self {
-                x: pos.x,
-                y: pos.y,
-                z: pos.z + 1,
+                pos: pos + Vec3::new(0, 0, 1),
                 plane: Plane::XY,
             },
         ]
@@ -243,7 +173,7 @@ impl SideSet {
     }
 
     fn side_index(side: &Side) -> usize {
-        ((side.x + 1) as usize) * 20 + ((side.y + 1) as usize)
+        ((side.pos.x() + 1) as usize) * 20 + ((side.pos.y() + 1) as usize)
     }
 
     fn side_mask(side: &Side) -> u64 {
@@ -253,7 +183,7 @@ impl SideSet {
             Plane::YZ => 2,
         };
         println!("{:?}", side);
-        1 << (((side.z + 1) as usize) * 3 + plane)
+        1 << (((side.pos.z() + 1) as usize) * 3 + plane)
     }
 }
 
@@ -1,9 +1,19 @@
-use std::collections::BinaryHeap;
+use log::debug;
+use std::{fmt::Write, num::NonZeroUsize, sync::OnceLock, thread};
 
-use crate::Day;
+use crate::{
+    explain::Explain,
+    optimize::{branch_and_bound, branch_and_bound_path, BranchAndBound, Stats},
+    Day,
+};
 
 #[derive(Clone)]
-pub struct Day19(Vec<Blueprint>);
+pub struct Day19 {
+    blueprints: Vec<Blueprint>,
+    /// Set once each part has logged its search statistics, so that timing
+    /// it many times doesn't log them again.
+    stats_logged: [OnceLock<()>; 2],
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Material {
@@ -23,6 +33,27 @@ impl Material {
             _ => panic!("Unknown material: {raw}"),
         }
     }
+
+    const ALL: [Self; 4] = [Self::Ore, Self::Clay, Self::Obsidian, Self::Geode];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Ore => "ore",
+            Self::Clay => "clay",
+            Self::Obsidian => "obsidian",
+            Self::Geode => "geode",
+        }
+    }
+
+    /// The robot which gathers this material, with its article.
+    const fn robot(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Ore => ("an", "ore-collecting robot"),
+            Self::Clay => ("a", "clay-collecting robot"),
+            Self::Obsidian => ("an", "obsidian-collecting robot"),
+            Self::Geode => ("a", "geode-cracking robot"),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -83,6 +114,15 @@ impl MaterialCounts {
         }
     }
 
+    const fn get(&self, material: Material) -> usize {
+        match material {
+            Material::Ore => self.ore,
+            Material::Clay => self.clay,
+            Material::Obsidian => self.obsidian,
+            Material::Geode => self.geode,
+        }
+    }
+
     fn min_copies_of(&self, other: &Self) -> Option<usize> {
         let copies = [
             (self.ore, other.ore),
@@ -201,6 +241,15 @@ impl Blueprint {
         }
     }
 
+    const fn recipe(&self, material: Material) -> MaterialCounts {
+        match material {
+            Material::Ore => self.ore,
+            Material::Clay => self.clay,
+            Material::Obsidian => self.obsidian,
+            Material::Geode => self.geode,
+        }
+    }
+
     const fn recipes(&self) -> [(MaterialCounts, MaterialCounts); 4] {
         [
             (ONE_ORE, self.ore),
@@ -282,53 +331,257 @@ impl State {
             .collect()
     }
 
-    fn best_geodes(self) -> usize {
-        let mut queue = BinaryHeap::new();
-        queue.push(self);
-        let mut max_geodes = 0;
-        while let Some(state) = queue.pop() {
-            max_geodes = max_geodes.max(state.lower_bound());
-            for child in state.children() {
-                if child.upper_bound() > max_geodes {
-                    queue.push(child);
+    fn best_geodes(self) -> (usize, Stats) {
+        let solution = branch_and_bound(&Geodes(self));
+        (solution.value, solution.stats)
+    }
+
+    /// Describe the best plan minute by minute, in the puzzle's own words,
+    /// returning the geodes opened too.
+    fn explain(self) -> (usize, String) {
+        let (solution, path) = branch_and_bound_path(&Geodes(self));
+        let mut builds = vec![None; self.turns + 1];
+        for pair in path.windows(2) {
+            let built = Material::ALL
+                .into_iter()
+                .find(|&material| pair[1].robots.get(material) > pair[0].robots.get(material));
+            builds[self.turns - pair[1].turns] = built;
+        }
+
+        let mut explanation = String::new();
+        let (mut inventory, mut robots) = (self.inventory, self.robots);
+        for (minute, build) in builds.into_iter().enumerate().skip(1) {
+            writeln!(explanation, "== Minute {minute} ==").unwrap();
+            if let Some(material) = build {
+                let recipe = self.blueprint.recipe(material);
+                inventory -= recipe;
+                let cost: Vec<_> = Material::ALL
+                    .into_iter()
+                    .filter(|&ingredient| recipe.get(ingredient) > 0)
+                    .map(|ingredient| format!("{} {}", recipe.get(ingredient), ingredient.name()))
+                    .collect();
+                let (article, robot) = material.robot();
+                writeln!(
+                    explanation,
+                    "Spend {} to start building {article} {robot}.",
+                    cost.join(" and ")
+                )
+                .unwrap();
+            }
+            for material in Material::ALL {
+                let count = robots.get(material);
+                if count == 0 {
+                    continue;
                 }
+                inventory.increase(material, count);
+                let total = inventory.get(material);
+                let robot = material.robot().1;
+                match (material, count) {
+                    (Material::Geode, 1) => writeln!(
+                        explanation,
+                        "1 {robot} cracks 1 geode; you now have {total} open geode{}.",
+                        if total == 1 { "" } else { "s" }
+                    ),
+                    (Material::Geode, _) => writeln!(
+                        explanation,
+                        "{count} {robot}s crack {count} geodes; you now have {total} open geodes."
+                    ),
+                    (_, 1) => writeln!(
+                        explanation,
+                        "1 {robot} collects 1 {0}; you now have {total} {0}.",
+                        material.name()
+                    ),
+                    (_, _) => writeln!(
+                        explanation,
+                        "{count} {robot}s collect {count} {0}; you now have {total} {0}.",
+                        material.name()
+                    ),
+                }
+                .unwrap();
+            }
+            if let Some(material) = build {
+                robots.increase(material, 1);
+                writeln!(
+                    explanation,
+                    "The new {} is ready; you now have {} of them.",
+                    material.robot().1,
+                    robots.get(material)
+                )
+                .unwrap();
             }
+            explanation.push('\n');
         }
-        max_geodes
+        debug_assert_eq!(inventory.geode, solution.value);
+        (solution.value, explanation)
+    }
+}
+
+/// Maximising the geodes opened from a starting state.
+struct Geodes(State);
+
+impl BranchAndBound for Geodes {
+    type Node = State;
+    /// The turns remaining and robots built. States with the same key are
+    /// compared by inventory, so this catches exact repeats as well as states
+    /// which are no better than one already seen.
+    type Key = (usize, MaterialCounts);
+
+    fn root(&self) -> State {
+        self.0
+    }
+
+    fn children(&self, state: &State) -> Vec<State> {
+        state.children()
+    }
+
+    fn lower_bound(&self, state: &State) -> usize {
+        state.lower_bound()
+    }
+
+    fn upper_bound(&self, state: &State) -> usize {
+        state.upper_bound()
+    }
+
+    fn key(&self, state: &State) -> Option<Self::Key> {
+        Some((state.turns, state.robots))
+    }
+
+    fn dominates(&self, state: &State, other: &State) -> bool {
+        state.inventory.is_superset(&other.inventory)
     }
 }
 
-impl std::cmp::Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.lower_bound().cmp(&other.lower_bound())
+impl Explain for Day19 {
+    fn explain_part1(&self) -> String {
+        let mut explanation = String::new();
+        let mut total = 0;
+        for &blueprint in &self.blueprints {
+            let (geodes, steps) = State::initial(blueprint, 24).explain();
+            let quality = geodes * blueprint.id;
+            total += quality;
+            write!(
+                explanation,
+                "Blueprint {}:\n\n{steps}Blueprint {} opens {geodes} geodes, \
+                 for a quality level of {quality}.\n\n",
+                blueprint.id, blueprint.id
+            )
+            .unwrap();
+        }
+        explanation + &format!("Total quality level: {total}")
+    }
+
+    fn explain_part2(&self) -> String {
+        let mut explanation = String::new();
+        let mut product = 1;
+        for &blueprint in self.blueprints.iter().take(3) {
+            let (geodes, steps) = State::initial(blueprint, 32).explain();
+            product *= geodes;
+            write!(
+                explanation,
+                "Blueprint {}:\n\n{steps}Blueprint {} opens {geodes} geodes.\n\n",
+                blueprint.id, blueprint.id
+            )
+            .unwrap();
+        }
+        explanation + &format!("Product of geodes opened: {product}")
     }
 }
 
-impl std::cmp::PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl Day19 {
+    /// The most geodes each blueprint can open in `turns`, and the search
+    /// statistics for each, splitting the blueprints between as many threads
+    /// as there are CPUs.
+    fn best_geodes(blueprints: &[Blueprint], turns: usize) -> Vec<(usize, Stats)> {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let chunk_size = blueprints.len().div_ceil(threads).max(1);
+        thread::scope(|scope| {
+            // Collected so every search starts before any is waited for.
+            #[allow(clippy::needless_collect)]
+            let searches: Vec<_> = blueprints
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&blueprint| State::initial(blueprint, turns).best_geodes())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            searches
+                .into_iter()
+                .flat_map(|search| search.join().unwrap())
+                .collect()
+        })
+    }
+
+    /// The most geodes each blueprint can open in `turns`, logging the search
+    /// statistics the first time `part` runs.
+    fn geodes(&self, part: usize, blueprints: &[Blueprint], turns: usize) -> Vec<usize> {
+        let results = Self::best_geodes(blueprints, turns);
+        self.stats_logged[part - 1].get_or_init(|| {
+            for (blueprint, (geodes, stats)) in blueprints.iter().zip(&results) {
+                debug!(
+                    "Blueprint {} opens {geodes} geodes in {turns} minutes: {stats}",
+                    blueprint.id
+                );
+            }
+        });
+        results.into_iter().map(|(geodes, _)| geodes).collect()
     }
 }
 
 impl Day for Day19 {
     fn parse(input: &'static str) -> Self {
-        Self(input.lines().map(Blueprint::parse).collect())
+        Self {
+            blueprints: input.lines().map(Blueprint::parse).collect(),
+            stats_logged: Default::default(),
+        }
     }
 
     fn part1(&self) -> String {
-        self.0
-            .iter()
-            .map(|&blueprint| State::initial(blueprint, 24).best_geodes() * blueprint.id)
+        self.geodes(1, &self.blueprints, 24)
+            .into_iter()
+            .zip(&self.blueprints)
+            .map(|(geodes, blueprint)| geodes * blueprint.id)
             .sum::<usize>()
             .to_string()
     }
 
     fn part2(&self) -> String {
-        self.0
-            .iter()
-            .take(3)
-            .map(|&blueprint| State::initial(blueprint, 32).best_geodes())
+        let blueprints = &self.blueprints[..self.blueprints.len().min(3)];
+        self.geodes(2, blueprints, 32)
+            .into_iter()
             .product::<usize>()
             .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.
+";
+
+    #[test]
+    fn test_example() {
+        let day = Day19::parse(EXAMPLE);
+        assert_eq!(day.part1(), "33");
+        // There are fewer than three blueprints, so part 2 uses both.
+        assert_eq!(day.part2(), (56 * 62).to_string());
+    }
+
+    #[test]
+    fn test_stats() {
+        let day = Day19::parse(EXAMPLE);
+        let (geodes, stats) = State::initial(day.blueprints[0], 24).best_geodes();
+        assert_eq!(geodes, 9);
+        assert!(stats.expanded > 0);
+        assert!(stats.pruned_by_bound > 0);
+        assert!(stats.pruned_by_dominance > 0);
+        assert!(stats.max_queue > 0);
+    }
+}
@@ -1,10 +1,16 @@
-mod cube_fold;
 pub(self) mod parse;
 mod part1;
 mod part2;
 
-use crate::Day;
-pub(self) use cube_fold::{face_size, Cube, Face};
+use std::sync::OnceLock;
+
+use log::{debug, error, warn};
+
+use crate::{
+    vec2::Vec2,
+    visualize::{Recorder, Visualize},
+    Day,
+};
 pub(self) use parse::{Instruction, Tile, Turn};
 pub(self) use part1::{BasicMap, BasicPosition};
 use part2::CubeMap;
@@ -13,6 +19,20 @@ use part2::CubeMap;
 pub struct Day22 {
     map: BasicMap,
     instructions: Vec<Instruction>,
+    /// Set once part 2 has logged how the map folds into a cube, so that
+    /// timing it many times doesn't log it again.
+    fold_logged: OnceLock<()>,
+}
+
+/// A position on the net after a move or turn while following instructions.
+#[derive(Copy, Clone, Debug)]
+pub struct Step {
+    /// The index of the instruction being followed.
+    pub instruction: usize,
+    pub position: BasicPosition,
+    /// Where the move started, if it wrapped around to a part of the net
+    /// which isn't next to it.
+    pub wrapped_from: Option<Vec2>,
 }
 
 trait Map {
@@ -23,11 +43,15 @@ trait Map {
     fn tile_at(&self, pos: Self::Position) -> Tile;
     fn start_point(&self) -> Self::Position;
     fn score_position(&self, pos: Self::Position) -> usize;
+    fn net_position(&self, pos: Self::Position) -> BasicPosition;
 
+    /// Follow an instruction, calling `on_step` with the position after each
+    /// move or turn.
     fn follow_instruction(
         &self,
         mut pos: Self::Position,
         instruction: Instruction,
+        mut on_step: impl FnMut(Self::Position),
     ) -> Self::Position {
         match instruction {
             Instruction::Move(n) => {
@@ -37,20 +61,72 @@ trait Map {
                         break;
                     }
                     pos = new_pos;
+                    on_step(pos);
                 }
                 pos
             }
-            Instruction::Turn(turn) => Self::turn_position(pos, turn),
+            Instruction::Turn(turn) => {
+                pos = Self::turn_position(pos, turn);
+                on_step(pos);
+                pos
+            }
         }
     }
 
     fn score_instructions(&self, instructions: &[Instruction]) -> usize {
         let mut pos = self.start_point();
         for &instruction in instructions {
-            pos = self.follow_instruction(pos, instruction);
+            pos = self.follow_instruction(pos, instruction, |_| {});
         }
         self.score_position(pos)
     }
+
+    /// Follow the instructions, recording every step from the start point.
+    fn trace_instructions(&self, instructions: &[Instruction]) -> Vec<Step> {
+        let mut pos = self.start_point();
+        let mut steps = vec![Step {
+            instruction: 0,
+            position: self.net_position(pos),
+            wrapped_from: None,
+        }];
+        for (index, &instruction) in instructions.iter().enumerate() {
+            pos = self.follow_instruction(pos, instruction, |pos| {
+                let last = steps.last().unwrap().position;
+                let position = self.net_position(pos);
+                let moved = position.pos != last.pos;
+                steps.push(Step {
+                    instruction: index,
+                    position,
+                    wrapped_from: (moved && position.pos != last.pos + last.dir)
+                        .then_some(last.pos),
+                });
+            });
+        }
+        steps
+    }
+}
+
+impl Day22 {
+    /// Show the instructions being followed on a map in 50 frames.
+    fn record_trace(&self, map: &impl Map, recorder: &mut Recorder) {
+        let steps = map.trace_instructions(&self.instructions);
+        let per_frame = self.instructions.len().div_ceil(50).max(1);
+        for end in (per_frame..self.instructions.len()).step_by(per_frame) {
+            let shown = steps.partition_point(|step| step.instruction < end);
+            recorder.record(self.map.draw_trace(&steps[..shown]));
+        }
+        recorder.record(self.map.draw_trace(&steps));
+    }
+}
+
+impl Visualize for Day22 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        self.record_trace(&self.map, recorder);
+        match CubeMap::try_from(self.map.clone()) {
+            Ok(cube) => self.record_trace(&cube, recorder),
+            Err(err) => warn!("not tracing the cube, as the map doesn't fold into one: {err}"),
+        }
+    }
 }
 
 impl Day for Day22 {
@@ -58,7 +134,11 @@ impl Day for Day22 {
         let (raw_map, raw_instructions) = input.split_once("\n\n").unwrap();
         let map = BasicMap::parse(raw_map);
         let instructions = parse::instructions(raw_instructions);
-        Self { map, instructions }
+        Self {
+            map,
+            instructions,
+            fold_logged: OnceLock::new(),
+        }
     }
 
     fn part1(&self) -> String {
@@ -66,8 +146,92 @@ impl Day for Day22 {
     }
 
     fn part2(&self) -> String {
-        CubeMap::from(self.map.clone())
-            .score_instructions(&self.instructions)
-            .to_string()
+        match CubeMap::try_from(self.map.clone()) {
+            Ok(cube) => {
+                self.fold_logged
+                    .get_or_init(|| debug!("folded {}", cube.shape()));
+                cube.score_instructions(&self.instructions).to_string()
+            }
+            Err(err) => {
+                self.fold_logged
+                    .get_or_init(|| error!("map doesn't fold into a cube: {err}"));
+                err.to_string()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+
+    /// Trace the example on a map, checking the trace ends where the
+    /// instructions do and returning how many steps it has.
+    fn check_trace(day: &Day22, map: &impl Map) -> usize {
+        let steps = day.map.trace_instructions(&[]);
+        assert_eq!(steps.len(), 1);
+        let steps = map.trace_instructions(&day.instructions);
+        let end = steps.last().unwrap().position;
+        assert_eq!(
+            day.map.score_position(end),
+            map.score_instructions(&day.instructions)
+        );
+        // Each turn is one step, and each move at most as many as it asks for.
+        for (index, instruction) in day.instructions.iter().enumerate() {
+            let taken = steps
+                .iter()
+                .filter(|step| step.instruction == index)
+                .count();
+            match instruction {
+                Instruction::Move(n) => assert!(taken <= *n),
+                Instruction::Turn(_) => assert_eq!(taken, 1),
+            }
+        }
+
+        // The latest wrap's letter is drawn over any earlier one.
+        let wraps: Vec<_> = steps
+            .iter()
+            .enumerate()
+            .filter_map(|(index, step)| Some((index, step.wrapped_from?, step.position.pos)))
+            .collect();
+        assert!(!wraps.is_empty());
+        for ((index, from, to), letter) in wraps.into_iter().zip('a'..) {
+            let grid = day.map.draw_trace(&steps[..=index]);
+            assert_eq!((grid[from], grid[to]), (letter, letter));
+        }
+        steps.len()
+    }
+
+    #[test]
+    fn test_example() {
+        let day = Day22::parse(EXAMPLE);
+        assert_eq!(day.part1(), "6032");
+        assert_eq!(day.part2(), "5031");
+        let cube = CubeMap::try_from(day.map.clone()).unwrap();
+        assert_eq!(check_trace(&day, &day.map), 27);
+        assert_eq!(check_trace(&day, &cube), 32);
+    }
+
+    #[test]
+    fn test_not_a_cube() {
+        // The right shape for a net of 1 by 1 faces, but with seven of them.
+        let day = Day22::parse("....\n...\n    \n\n1");
+        assert_eq!(day.part1(), "1008");
+        assert_eq!(day.part2(), "a cube net has 6 faces, not 7");
     }
 }
@@ -1,3 +1,8 @@
+use crate::vec2::{
+    transform::{Rotate90, Transform},
+    Vec2,
+};
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Tile {
     Open,
@@ -19,6 +24,14 @@ impl Turn {
             _ => panic!("Invalid turn: {c}"),
         }
     }
+
+    /// Turn a direction to face left or right of it.
+    pub fn apply<S>(self, dir: Vec2<S>) -> Vec2<S> {
+        match self {
+            Self::Left => Rotate90::anticlockwise().apply(dir),
+            Self::Right => Rotate90::clockwise().apply(dir),
+        }
+    }
 }
 
 impl Tile {
@@ -1,6 +1,8 @@
+use crate::cube::face_size;
+use crate::grid::Grid;
 use crate::vec2::Vec2;
 
-use super::{face_size, Map, Tile, Turn};
+use super::{Map, Step, Tile, Turn};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct BasicPosition {
@@ -10,7 +12,7 @@ pub struct BasicPosition {
 
 #[derive(Clone, Debug)]
 pub struct BasicMap {
-    pub map: Vec<Vec<Tile>>,
+    pub map: Grid<Tile>,
     width: isize,
     height: isize,
     pub face_size: usize,
@@ -18,24 +20,43 @@ pub struct BasicMap {
 
 impl BasicMap {
     pub fn parse(raw: &str) -> Self {
-        let width = raw.lines().map(str::len).max().unwrap();
-        let lines: Vec<Vec<_>> = raw
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(Tile::from_char)
-                    .chain(std::iter::repeat(Tile::Void))
-                    .take(width)
-                    .collect()
-            })
-            .collect();
-        let height = lines.len();
+        let map = Grid::parse_padded(raw, ' ', Tile::from_char);
+        let (width, height) = (map.width(), map.height());
         Self {
-            map: lines,
+            map,
             width: width as isize,
             height: height as isize,
-            face_size: face_size(width, height),
+            face_size: face_size(width, height).expect("map isn't 3 by 4 or 2 by 5 faces"),
+        }
+    }
+}
+
+impl BasicMap {
+    /// Draw a path onto the map with arrows, like the puzzle does. Both ends
+    /// of each move which wraps around are marked with the same letter.
+    pub fn draw_trace(&self, steps: &[Step]) -> Grid<char> {
+        let mut grid = self.map.map(|tile| match tile {
+            Tile::Open => '.',
+            Tile::Closed => '#',
+            Tile::Void => ' ',
+        });
+        for step in steps {
+            grid[step.position.pos] = match step.position.dir.coords() {
+                [1, 0] => '>',
+                [0, 1] => 'v',
+                [-1, 0] => '<',
+                [0, -1] => '^',
+                _ => panic!("Invalid direction: {:?}", step.position.dir),
+            };
         }
+        let wraps = steps
+            .iter()
+            .filter_map(|step| Some((step.wrapped_from?, step.position.pos)));
+        for ((from, to), letter) in wraps.zip(('a'..='z').cycle()) {
+            grid[from] = letter;
+            grid[to] = letter;
+        }
+        grid
     }
 }
 
@@ -45,14 +66,14 @@ impl Map for BasicMap {
     fn next_position(&self, mut pos: Self::Position) -> Self::Position {
         let step = |mut pos: Self::Position, factor: usize| {
             pos.pos += pos.dir * factor;
-            if pos.pos.0 < 0 {
-                pos.pos.0 += self.width;
-            } else if pos.pos.0 >= self.width {
-                pos.pos.0 -= self.width;
-            } else if pos.pos.1 < 0 {
-                pos.pos.1 += self.height;
-            } else if pos.pos.1 >= self.height {
-                pos.pos.1 -= self.height;
+            if pos.pos.x() < 0 {
+                pos.pos[0] += self.width;
+            } else if pos.pos.x() >= self.width {
+                pos.pos[0] -= self.width;
+            } else if pos.pos.y() < 0 {
+                pos.pos[1] += self.height;
+            } else if pos.pos.y() >= self.height {
+                pos.pos[1] -= self.height;
             }
             pos
         };
@@ -64,20 +85,21 @@ impl Map for BasicMap {
     }
 
     fn turn_position(pos: Self::Position, turn: Turn) -> Self::Position {
-        let dir = match turn {
-            Turn::Left => Vec2::new(pos.dir.1, -pos.dir.0),
-            Turn::Right => Vec2::new(-pos.dir.1, pos.dir.0),
-        };
-        Self::Position { dir, ..pos }
+        Self::Position {
+            dir: turn.apply(pos.dir),
+            ..pos
+        }
     }
 
     fn tile_at(&self, pos: Self::Position) -> Tile {
-        *pos.pos.index(&self.map)
+        self.map[pos.pos]
     }
 
     fn start_point(&self) -> Self::Position {
-        (0..self.width as usize)
-            .find(|&x| self.map[0][x] == Tile::Open)
+        self.map
+            .row(0)
+            .iter()
+            .position(|&tile| tile == Tile::Open)
             .map(|x| BasicPosition {
                 pos: Vec2::new(x as isize, 0),
                 dir: Vec2::new(1, 0),
@@ -85,14 +107,18 @@ impl Map for BasicMap {
             .unwrap()
     }
 
+    fn net_position(&self, pos: Self::Position) -> BasicPosition {
+        pos
+    }
+
     fn score_position(&self, pos: Self::Position) -> usize {
-        let facing_score = match pos.dir {
-            Vec2(1, 0, _) => 0,
-            Vec2(0, 1, _) => 1,
-            Vec2(-1, 0, _) => 2,
-            Vec2(0, -1, _) => 3,
+        let facing_score = match pos.dir.coords() {
+            [1, 0] => 0,
+            [0, 1] => 1,
+            [-1, 0] => 2,
+            [0, -1] => 3,
             _ => panic!("Invalid direction: {:?}", pos.dir),
         };
-        ((pos.pos.1 + 1) * 1000 + (pos.pos.0 + 1) * 4) as usize + facing_score
+        ((pos.pos.y() + 1) * 1000 + (pos.pos.x() + 1) * 4) as usize + facing_score
     }
 }
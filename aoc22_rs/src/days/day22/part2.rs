@@ -1,82 +1,96 @@
-use crate::vec2::Vec2;
+use crate::{
+    cube::{Edge, Net, NetError, Shape},
+    vec2::{
+        transform::{Invert, Transform, Translate},
+        Vec2,
+    },
+};
 
-use super::{BasicMap, BasicPosition, Cube, Face, Map};
+use super::{BasicMap, BasicPosition, Map, Tile};
 
 pub struct CubeMap {
     net: BasicMap,
-    cube: Cube,
+    cube: Net,
 }
 
+/// Coordinates relative to the top left corner of a face.
+#[derive(Copy, Clone, Debug)]
+pub struct OnFace;
+
 #[derive(Copy, Clone, Debug)]
 pub struct FacePosition {
-    face: Face,
-    position: BasicPosition,
+    /// The index of the face in [`Net::faces`].
+    face: usize,
+    pos: Vec2<OnFace>,
+    dir: Vec2<OnFace>,
+}
+
+/// Maps positions on a face to positions on the net.
+fn face_to_net(face: usize, cube: &Net) -> Translate<OnFace, ()> {
+    Translate::new(cube.faces[face].position * cube.face_size)
 }
 
 impl FacePosition {
-    fn as_net_position(&self, face_size: isize) -> BasicPosition {
-        let pos = self.face.position * face_size + self.position.pos;
+    fn as_net_position(&self, cube: &Net) -> BasicPosition {
+        let to_net = face_to_net(self.face, cube);
         BasicPosition {
-            pos,
-            ..self.position
+            pos: to_net.apply(self.pos),
+            dir: to_net.apply_direction(self.dir),
         }
     }
 
-    fn from_net_position(position: BasicPosition, cube: &Cube) -> Self {
-        let face = *cube.get_face(position.pos / cube.face_size);
-        let pos = position.pos % cube.face_size;
+    fn from_net_position(position: BasicPosition, cube: &Net) -> Self {
+        let face = cube.face_at(position.pos / cube.face_size).unwrap();
+        let to_face = face_to_net(face, cube).inverse();
         Self {
             face,
-            position: BasicPosition { pos, ..position },
+            pos: to_face.apply(position.pos),
+            dir: to_face.apply_direction(position.dir),
         }
     }
 
-    fn face_wrap(&self, cube: &Cube) -> Self {
+    const fn face_wrap(&self, cube: &Net) -> Self {
         let max = (cube.face_size - 1) as isize;
-        let Vec2(x, y, _) = self.position.pos;
-        let (face_idx, distance) = if x < 0 {
-            (self.face.left(), max - y)
+        let [x, y] = self.pos.coords();
+        let (edge, distance) = if x < 0 {
+            (Edge::Left, max - y)
         } else if y < 0 {
-            (self.face.top(), x)
+            (Edge::Top, x)
         } else if x > max {
-            (self.face.right(), y)
+            (Edge::Right, y)
         } else if y > max {
-            (self.face.bottom(), max - x)
+            (Edge::Bottom, max - x)
         } else {
             return *self;
         };
-        let face = *cube.get_face(face_idx);
-        let position = if self.face.position == face.left() {
-            BasicPosition {
-                pos: Vec2::new(0, distance),
-                dir: Vec2::new(1, 0),
-            }
-        } else if self.face.position == face.top() {
-            BasicPosition {
-                pos: Vec2::new(max - distance, 0),
-                dir: Vec2::new(0, 1),
-            }
-        } else if self.face.position == face.right() {
-            BasicPosition {
-                pos: Vec2::new(max, max - distance),
-                dir: Vec2::new(-1, 0),
-            }
-        } else if self.face.position == face.bottom() {
-            BasicPosition {
-                pos: Vec2::new(distance, max),
-                dir: Vec2::new(0, -1),
-            }
-        } else {
-            panic!("face adjacency is not mutual");
+        let join = cube.faces[self.face].join(edge);
+        let (pos, dir) = match join.edge {
+            Edge::Left => (Vec2::new(0, distance), Vec2::new(1, 0)),
+            Edge::Top => (Vec2::new(max - distance, 0), Vec2::new(0, 1)),
+            Edge::Right => (Vec2::new(max, max - distance), Vec2::new(-1, 0)),
+            Edge::Bottom => (Vec2::new(distance, max), Vec2::new(0, -1)),
         };
-        Self { face, position }
+        Self {
+            face: join.face,
+            pos,
+            dir,
+        }
     }
 }
 
-impl From<BasicMap> for CubeMap {
-    fn from(net: BasicMap) -> Self {
-        let cube = Cube::from_lines(&net.map, net.face_size);
-        Self { net, cube }
+impl CubeMap {
+    /// Which net the map is.
+    pub const fn shape(&self) -> Shape {
+        self.cube.shape
+    }
+}
+
+impl TryFrom<BasicMap> for CubeMap {
+    type Error = NetError;
+
+    fn try_from(net: BasicMap) -> Result<Self, NetError> {
+        let cube = Net::from_grid(&net.map, |&tile| tile != Tile::Void)?;
+        Ok(Self { net, cube })
     }
 }
 
@@ -84,12 +98,16 @@ impl Map for CubeMap {
     type Position = FacePosition;
 
     fn turn_position(mut pos: Self::Position, turn: super::Turn) -> Self::Position {
-        pos.position.dir = BasicMap::turn_position(pos.position, turn).dir;
+        pos.dir = turn.apply(pos.dir);
         pos
     }
 
+    fn net_position(&self, pos: Self::Position) -> BasicPosition {
+        pos.as_net_position(&self.cube)
+    }
+
     fn score_position(&self, pos: Self::Position) -> usize {
-        let pos = pos.as_net_position(self.cube.face_size as isize);
+        let pos = pos.as_net_position(&self.cube);
         self.net.score_position(pos)
     }
 
@@ -98,13 +116,13 @@ impl Map for CubeMap {
         FacePosition::from_net_position(net_position, &self.cube)
     }
 
-    fn tile_at(&self, pos: Self::Position) -> super::Tile {
-        let pos = pos.as_net_position(self.cube.face_size as isize);
+    fn tile_at(&self, pos: Self::Position) -> Tile {
+        let pos = pos.as_net_position(&self.cube);
         self.net.tile_at(pos)
     }
 
     fn next_position(&self, mut pos: Self::Position) -> Self::Position {
-        pos.position.pos += pos.position.dir;
+        pos.pos += pos.dir;
         pos.face_wrap(&self.cube)
     }
 }
@@ -1,6 +1,12 @@
-use rustc_hash::{FxHashSet, FxHashMap};
+use rustc_hash::FxHashMap;
 
-use crate::{Day, vec2::Vec2};
+use crate::{
+    grid::Grid,
+    pointset::{PointSet, SparseSet},
+    vec2::Vec2,
+    visualize::{Recorder, Visualize},
+    Day,
+};
 
 #[derive(Clone)]
 pub struct Day23(Cells);
@@ -49,21 +55,13 @@ const DIRECTIONS: [Direction; 4] = [
 impl Direction {
     const fn neighbours(self) -> [Neighbour; 3] {
         match self {
-            Direction::Up => [
-                Neighbour::TopLeft,
-                Neighbour::Top,
-                Neighbour::TopRight,
-            ],
+            Direction::Up => [Neighbour::TopLeft, Neighbour::Top, Neighbour::TopRight],
             Direction::Down => [
                 Neighbour::BottomLeft,
                 Neighbour::Bottom,
                 Neighbour::BottomRight,
             ],
-            Direction::Left => [
-                Neighbour::TopLeft,
-                Neighbour::Left,
-                Neighbour::BottomLeft,
-            ],
+            Direction::Left => [Neighbour::TopLeft, Neighbour::Left, Neighbour::BottomLeft],
             Direction::Right => [
                 Neighbour::TopRight,
                 Neighbour::Right,
@@ -153,7 +151,7 @@ impl Neighbours {
             moves |= 1 << Direction::Right.index();
         }
         if moves == 0b1111 {
-            moves = 0;  // Don't move at all if there are no neighbours
+            moves = 0; // Don't move at all if there are no neighbours
         }
         Moves(moves)
     }
@@ -163,7 +161,7 @@ impl Neighbours {
         let mut maybe_i = Some(0);
         while let Some(i) = maybe_i {
             table[i as usize] = Neighbours(i).moves();
-            maybe_i = i.checked_add(1);  // Once we reach 255, this will overflow.
+            maybe_i = i.checked_add(1); // Once we reach 255, this will overflow.
         }
         table
     }
@@ -172,14 +170,14 @@ impl Neighbours {
 const MOVES: [Moves; 256] = Neighbours::table();
 
 #[derive(Clone)]
-struct Cells(FxHashSet<Vec2>);
+struct Cells(SparseSet);
 
 impl Cells {
     fn parse(input: &str) -> Self {
-        let cells = input.lines().enumerate()
-            .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, c)| (x, y, c)))
-            .filter(|(_, _, c)| *c == '#')
-            .map(|(x, y, _)| Vec2::new(x as isize, y as isize))
+        let cells = Grid::parse(input, |c| c == '#')
+            .iter()
+            .filter(|(_, &elf)| elf)
+            .map(|(pos, _)| pos)
             .collect();
         Cells(cells)
     }
@@ -187,10 +185,10 @@ impl Cells {
     fn get_propositions(&self, round: usize) -> FxHashMap<Vec2, Vec2> {
         let round = round % 4;
         let mut propositions = FxHashMap::default();
-        for &elf in &self.0 {
+        for elf in self.0.iter() {
             let mut neighbours = 0;
             for neighbour in &NEIGHBOURS {
-                if self.0.contains(&(elf + neighbour.delta())) {
+                if self.0.contains(elf + neighbour.delta()) {
                     neighbours |= 1 << neighbour.index();
                 }
             }
@@ -215,21 +213,42 @@ impl Cells {
 
     fn apply_propositions(&mut self, propositions: &FxHashMap<Vec2, Vec2>) {
         for (proposition, elf) in propositions {
-            self.0.remove(elf);
+            self.0.remove(*elf);
             self.0.insert(*proposition);
         }
     }
 
     fn bounding_box(&self) -> (Vec2, Vec2) {
-        let mut min = Vec2::new(isize::MAX, isize::MAX);
-        let mut max = Vec2::new(isize::MIN, isize::MIN);
-        for &cell in &self.0 {
-            min.0 = min.0.min(cell.0);
-            min.1 = min.1.min(cell.1);
-            max.0 = max.0.max(cell.0);
-            max.1 = max.1.max(cell.1);
+        Vec2::bounding_box(self.0.iter()).unwrap()
+    }
+
+    fn draw(&self, recorder: &mut Recorder) {
+        let (min, max) = self.bounding_box();
+        let size = max - min + Vec2::new(1, 1);
+        let grid = Grid::from_fn(size.x() as usize, size.y() as usize, |pos| {
+            if self.0.contains(pos + min) {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        recorder.record_at(min, grid);
+    }
+}
+
+impl Visualize for Day23 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        let mut cells = self.0.clone();
+        for round in 0.. {
+            let propositions = cells.get_propositions(round);
+            if round % 5 == 0 || propositions.is_empty() {
+                cells.draw(recorder);
+            }
+            if propositions.is_empty() {
+                break;
+            }
+            cells.apply_propositions(&propositions);
         }
-        (min, max)
     }
 }
 
@@ -244,7 +263,7 @@ impl Day for Day23 {
             cells.apply_propositions(&cells.get_propositions(round));
         }
         let (min, max) = cells.bounding_box();
-        let area = (max.0 - min.0 + 1) * (max.1 - min.1 + 1);
+        let area = (max.x() - min.x() + 1) * (max.y() - min.y() + 1);
         (area - cells.0.len() as isize).to_string()
     }
 
@@ -260,3 +279,233 @@ impl Day for Day23 {
         unreachable!()
     }
 }
+
+/// The number of 64-bit lanes in a [`Row`].
+const LANES: usize = 4;
+
+/// One row of the map as a bitmask, with bit `i` for column `i`.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct Row([u64; LANES]);
+
+impl Row {
+    const WIDTH: usize = 64 * LANES;
+
+    /// The first and last columns, which the elves must not reach so that
+    /// moving a row never pushes one off the end.
+    const EDGES: Self = {
+        let mut edges = Self([0; LANES]);
+        edges.set(0);
+        edges.set(Self::WIDTH - 1);
+        edges
+    };
+
+    const fn is_empty(self) -> bool {
+        let mut i = 0;
+        while i < LANES {
+            if self.0[i] != 0 {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    fn count(self) -> u32 {
+        self.0.iter().map(|lane| lane.count_ones()).sum()
+    }
+
+    const fn set(&mut self, column: usize) {
+        self.0[column / 64] |= 1 << (column % 64);
+    }
+
+    /// Move every bit `n` columns right, towards higher columns.
+    fn right(self, n: u32) -> Self {
+        let mut row = Self::default();
+        for i in 0..LANES {
+            row.0[i] = self.0[i] << n;
+            if i > 0 {
+                row.0[i] |= self.0[i - 1] >> (64 - n);
+            }
+        }
+        row
+    }
+
+    /// Move every bit `n` columns left, towards lower columns.
+    fn left(self, n: u32) -> Self {
+        let mut row = Self::default();
+        for i in 0..LANES {
+            row.0[i] = self.0[i] >> n;
+            if i + 1 < LANES {
+                row.0[i] |= self.0[i + 1] << (64 - n);
+            }
+        }
+        row
+    }
+
+    /// The lowest and highest columns set.
+    fn columns(self) -> Option<(usize, usize)> {
+        let low = self.0.iter().position(|&lane| lane != 0)?;
+        let high = self.0.iter().rposition(|&lane| lane != 0)?;
+        Some((
+            low * 64 + self.0[low].trailing_zeros() as usize,
+            high * 64 + 63 - self.0[high].leading_zeros() as usize,
+        ))
+    }
+}
+
+impl std::ops::BitAnd for Row {
+    type Output = Self;
+
+    fn bitand(mut self, other: Self) -> Self {
+        for (lane, other) in self.0.iter_mut().zip(other.0) {
+            *lane &= other;
+        }
+        self
+    }
+}
+
+impl std::ops::BitOr for Row {
+    type Output = Self;
+
+    fn bitor(mut self, other: Self) -> Self {
+        for (lane, other) in self.0.iter_mut().zip(other.0) {
+            *lane |= other;
+        }
+        self
+    }
+}
+
+impl std::ops::Not for Row {
+    type Output = Self;
+
+    fn not(mut self) -> Self {
+        for lane in &mut self.0 {
+            *lane = !*lane;
+        }
+        self
+    }
+}
+
+/// The elves as a bitmask per row, simulating a whole row of elves at a time.
+/// There's always an empty row above and below the elves, and the columns
+/// are fixed, so the elves can't spread wider than [`Row::WIDTH`].
+#[derive(Clone)]
+struct Bits(Vec<Row>);
+
+impl Bits {
+    fn parse(input: &str) -> Self {
+        let grid = Grid::parse(input, |c| c == '#');
+        assert!(grid.width() <= Row::WIDTH / 2, "too wide to spread out");
+        let offset = (Row::WIDTH - grid.width()) / 2;
+        let mut rows = vec![Row::default()];
+        rows.extend(grid.rows().map(|cells| {
+            let mut row = Row::default();
+            for (x, _) in cells.iter().enumerate().filter(|(_, &elf)| elf) {
+                row.set(offset + x);
+            }
+            row
+        }));
+        rows.push(Row::default());
+        Self(rows)
+    }
+
+    /// Run one round, returning whether any elf moved.
+    fn round(&mut self, round: usize) -> bool {
+        let rows = &self.0;
+        let empty = Row::default();
+        let row = |y: usize| rows.get(y).copied().unwrap_or(empty);
+        // The elves which propose moving in each direction, per row, indexed
+        // by `Direction::index`.
+        let mut proposals = vec![[empty; 4]; rows.len()];
+        for (y, proposed) in proposals.iter_mut().enumerate() {
+            let (above, here, below) = (row(y.wrapping_sub(1)), row(y), row(y + 1));
+            assert!((here & Row::EDGES).is_empty(), "elves spread too wide");
+            let column = above | here | below;
+            // Whether each direction has no elves in it, like `Neighbours::moves`.
+            let mut free = [empty; 4];
+            free[Direction::Up.index()] = !(above | above.left(1) | above.right(1));
+            free[Direction::Down.index()] = !(below | below.left(1) | below.right(1));
+            free[Direction::Left.index()] = !column.right(1);
+            free[Direction::Right.index()] = !column.left(1);
+            let alone = free.iter().fold(here, |alone, &free| alone & free);
+            let mut undecided = here & !alone;
+            for direction in DIRECTIONS.iter().cycle().skip(round % 4).take(4) {
+                let index = direction.index();
+                proposed[index] = undecided & free[index];
+                undecided = undecided & !free[index];
+            }
+        }
+
+        // Two elves can only propose the same cell from opposite sides.
+        let up = Direction::Up.index();
+        let down = Direction::Down.index();
+        let (left, right) = (Direction::Left.index(), Direction::Right.index());
+        let proposed = |y: usize, direction: usize| {
+            proposals
+                .get(y)
+                .map_or(empty, |proposed| proposed[direction])
+        };
+        let mut moved = false;
+        let mut next = vec![empty; rows.len()];
+        for y in 0..rows.len() {
+            let p = proposals[y];
+            let ups = p[up] & !proposed(y.wrapping_sub(2), down);
+            let downs = p[down] & !proposed(y + 2, up);
+            let lefts = p[left] & !p[right].right(2);
+            let rights = p[right] & !p[left].left(2);
+            let leaving = ups | downs | lefts | rights;
+            moved |= !leaving.is_empty();
+            let arriving_up = proposed(y + 1, up) & !proposed(y.wrapping_sub(1), down);
+            let arriving_down = proposed(y.wrapping_sub(1), down) & !proposed(y + 1, up);
+            let arriving_across = lefts.left(1) | rights.right(1);
+            next[y] = (rows[y] & !leaving) | arriving_up | arriving_down | arriving_across;
+        }
+
+        // Keep an empty row at the top and bottom.
+        if !next[0].is_empty() {
+            next.insert(0, empty);
+        }
+        if !next[next.len() - 1].is_empty() {
+            next.push(empty);
+        }
+        self.0 = next;
+        moved
+    }
+
+    fn empty_ground(&self) -> usize {
+        let top = self.0.iter().position(|row| !row.is_empty()).unwrap();
+        let bottom = self.0.iter().rposition(|row| !row.is_empty()).unwrap();
+        let height = bottom - top + 1;
+        let all = self.0.iter().fold(Row::default(), |all, &row| all | row);
+        let (low, high) = all.columns().unwrap();
+        let elves: u32 = self.0.iter().map(|row| row.count()).sum();
+        height * (high - low + 1) - elves as usize
+    }
+}
+
+/// The same as [`Day23`], but using bitmasks for whole rows of elves.
+#[derive(Clone)]
+pub struct Day23Bitwise(Bits);
+
+impl Day for Day23Bitwise {
+    fn parse(input: &str) -> Self {
+        Self(Bits::parse(input))
+    }
+
+    fn part1(&self) -> String {
+        let mut bits = self.0.clone();
+        for round in 0..10 {
+            bits.round(round);
+        }
+        bits.empty_ground().to_string()
+    }
+
+    fn part2(&self) -> String {
+        let mut bits = self.0.clone();
+        let mut round = 0;
+        while bits.round(round) {
+            round += 1;
+        }
+        (round + 1).to_string()
+    }
+}
@@ -1,58 +1,41 @@
-use std::collections::BinaryHeap;
-
-use rustc_hash::FxHashSet;
-
-use crate::{Day, vec2::{Vec2, CARDINALS}};
+use crate::{
+    grid::Grid,
+    vec2::{Vec2, CARDINALS},
+    visualize::{Recorder, Visualize},
+    Day,
+};
 
 #[derive(Clone)]
 pub struct Day24(Valley);
 
+/// The valley's blizzards, as a bitmask of where they start in each row for
+/// each direction they blow in. Each blizzard stays in its row or column, so
+/// where they are at any time can be found by rotating or picking rows, and
+/// the whole valley repeats every `period` minutes.
 #[derive(Clone)]
 struct Valley {
-    generations: Vec<Map>,
     width: usize,
     height: usize,
-    blizzards: Vec<Blizzard>,
+    period: usize,
+    up: Vec<u128>,
+    down: Vec<u128>,
+    left: Vec<u128>,
+    right: Vec<u128>,
 }
 
-#[derive(Clone)]
-struct Map(Vec<Vec<bool>>);
-
-impl Map {
-    fn occupied(&self, position: Vec2) -> bool {
-        let max_x = self.0[0].len() as isize - 1;
-        let max_y = self.0.len() as isize - 1;
-        if position.0 < 0 || position.0 > max_x {
-            true
-        } else if position == Vec2::new(0, -1) || position == Vec2::new(max_x, max_y + 1) {
-            false
-        } else if position.1 < 0 || position.1 > max_y {
-            true
-        } else {
-            self.0[position.1 as usize][position.0 as usize]
-        }
+const fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
     }
+    a
 }
 
-#[derive(Clone, Copy)]
-struct Blizzard {
-    position: Vec2,
-    direction: Vec2,
-}
-
-impl Blizzard {
-    fn advance(&mut self, width: usize, height: usize) {
-        let (max_x, max_y) = (width as isize - 1, height as isize - 1);
-        self.position += self.direction;
-        if self.position.0 < 0 {
-            self.position.0 = max_x;
-        } else if self.position.0 > max_x {
-            self.position.0 = 0;
-        } else if self.position.1 < 0 {
-            self.position.1 = max_y;
-        } else if self.position.1 > max_y {
-            self.position.1 = 0;
-        }
+/// Rotate the lowest `width` bits of a row towards bit 0.
+const fn rotate_down(row: u128, by: usize, width: usize) -> u128 {
+    if by == 0 {
+        row
+    } else {
+        row >> by | (row << (width - by) & u128::MAX >> (128 - width))
     }
 }
 
@@ -60,94 +43,166 @@ impl Valley {
     fn parse(input: &str) -> Self {
         let width = input.find('\n').unwrap() - 2;
         let height = input.lines().count() - 2;
-        let blizzards = input.lines().enumerate()
-            .flat_map(|(y, line)| line.chars().enumerate().map(move |(x, c)| (x as isize - 1, y as isize - 1, c)))
-            .flat_map(|(x, y, c)| match c {
-                '^' => Some(Blizzard { position: Vec2::new(x, y), direction: Vec2::new(0, -1) }),
-                'v' => Some(Blizzard { position: Vec2::new(x, y), direction: Vec2::new(0, 1) }),
-                '<' => Some(Blizzard { position: Vec2::new(x, y), direction: Vec2::new(-1, 0) }),
-                '>' => Some(Blizzard { position: Vec2::new(x, y), direction: Vec2::new(1, 0) }),
-                _ => None,
-            })
-            .collect();
-        let generations = Vec::new();
-        Self { generations, width, height, blizzards }
-    }
-
-    fn generation(&mut self, index: usize) -> &Map {
-        while index >= self.generations.len() {
-            self.simulate_generation();
+        assert!(width <= 128, "valley is too wide");
+        let mut valley = Self {
+            width,
+            height,
+            period: width * height / gcd(width, height),
+            up: vec![0; height],
+            down: vec![0; height],
+            left: vec![0; height],
+            right: vec![0; height],
+        };
+        for (pos, &c) in Grid::parse(input, |c| c).iter() {
+            let (x, y) = (pos.x() as usize, pos.y() as usize);
+            let rows = match c {
+                '^' => &mut valley.up,
+                'v' => &mut valley.down,
+                '<' => &mut valley.left,
+                '>' => &mut valley.right,
+                _ => continue,
+            };
+            rows[y - 1] |= 1 << (x - 1);
         }
-        &self.generations[index]
+        valley
     }
 
-    fn simulate_generation(&mut self) {
-        let mut map = Map(vec![vec![false; self.width]; self.height]);
-        for blizzard in &mut self.blizzards {
-            *blizzard.position.index_mut(&mut map.0) = true;
-            blizzard.advance(self.width, self.height);
+    const ENTRANCE: Vec2 = Vec2::new(0, -1);
+
+    const fn exit(&self) -> Vec2 {
+        Vec2::new(self.width as isize - 1, self.height as isize)
+    }
+
+    fn inside(&self, position: Vec2) -> bool {
+        (0..self.width as isize).contains(&position.x())
+            && (0..self.height as isize).contains(&position.y())
+    }
+
+    /// The columns with a blizzard in a row at a time.
+    fn blizzards(&self, y: usize, time: usize) -> u128 {
+        let (width, height) = (self.width, self.height);
+        let (across, down) = (time % width, time % height);
+        rotate_down(self.left[y], across, width)
+            | rotate_down(self.right[y], (width - across) % width, width)
+            | self.up[(y + down) % height]
+            | self.down[(y + height - down) % height]
+    }
+
+    /// Whether the expedition can be at a position at a time.
+    fn open(&self, position: Vec2, time: usize) -> bool {
+        if position == Self::ENTRANCE || position == self.exit() {
+            true
+        } else {
+            self.inside(position)
+                && self.blizzards(position.y() as usize, time) >> position.x() & 1 == 0
         }
-        self.generations.push(map);
     }
-}
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash)]
-struct State {
-    position: Vec2,
-    steps: usize,
-    target: Vec2,
-}
+    /// Draw the valley and its walls at a time, with the expedition.
+    fn draw(&self, time: usize, expedition: Vec2) -> Grid<char> {
+        Grid::from_fn(self.width + 2, self.height + 2, |pos| {
+            let pos = pos - Vec2::new(1, 1);
+            if pos == expedition {
+                'E'
+            } else if self.open(pos, time) {
+                '.'
+            } else if self.inside(pos) {
+                '*'
+            } else {
+                '#'
+            }
+        })
+    }
 
-impl State {
-    fn lower_bound(&self) -> usize {
-        self.steps + (self.target - self.position).manhattan()
-    }
-
-    fn children(&self, valley: &mut Valley) -> Vec<Self> {
-        let steps = self.steps + 1;
-        let map = valley.generation(steps);
-        CARDINALS
-            .iter()
-            .chain(std::iter::once(&Vec2::new(0, 0)))
-            .flat_map(move |&direction| {
-                let position = self.position + direction;
-                if map.occupied(position) {
-                    None
-                } else {
-                    Some(State { position, steps, ..*self })
-                }
-            })
-            .collect()
-    }
-
-    fn best_path(self, valley: &mut Valley) -> Option<Self> {
-        let mut queue = BinaryHeap::new();
-        queue.push(self);
-        let mut visited = FxHashSet::default();
-        while let Some(state) = queue.pop() {
-            if state.position == state.target {
-                return Some(state);
+    /// The expedition's positions each minute on the quickest way from one
+    /// end of the valley to the other, setting off at a time.
+    ///
+    /// This is a breadth-first search a minute at a time, keeping every cell
+    /// the expedition could be in as a bitmask per row. Where it can go next
+    /// only depends on those cells and the time modulo the period, so if they
+    /// repeat a period later, the other end can't be reached.
+    fn cross(&self, from: Vec2, to: Vec2, time: usize) -> Option<Vec<Vec2>> {
+        let inside = |end: Vec2| Vec2::new(end.x(), end.y().clamp(0, self.height as isize - 1));
+        let (entry, goal) = (inside(from), inside(to));
+        let mask = u128::MAX >> (128 - self.width);
+        let is_set = |rows: &[u128], pos: Vec2| {
+            self.inside(pos) && rows[pos.y() as usize] >> pos.x() & 1 == 1
+        };
+        // The cells inside the valley the expedition could be in each minute,
+        // as well as still waiting at the start.
+        let mut reachable = vec![vec![0; self.height]];
+        while !is_set(reachable.last().unwrap(), goal) {
+            let minutes = reachable.len();
+            if minutes > self.period
+                && reachable[minutes - 1] == reachable[minutes - 1 - self.period]
+            {
+                return None;
             }
-            for child in state.children(valley) {
-                if visited.insert(child) {
-                    queue.push(child);
-                }
+            let rows = &reachable[minutes - 1];
+            let next = (0..self.height)
+                .map(|y| {
+                    let mut row = rows[y] | rows[y] << 1 | rows[y] >> 1;
+                    if y > 0 {
+                        row |= rows[y - 1];
+                    }
+                    if y + 1 < self.height {
+                        row |= rows[y + 1];
+                    }
+                    if y == entry.y() as usize {
+                        row |= 1 << entry.x();
+                    }
+                    row & mask & !self.blizzards(y, time + minutes)
+                })
+                .collect();
+            reachable.push(next);
+        }
+
+        // Follow the path back from the goal, through any cell which could
+        // have been reached a minute earlier.
+        let mut positions = vec![to, goal];
+        let mut position = goal;
+        for rows in reachable.iter().rev().skip(1) {
+            if position != from {
+                position = CARDINALS
+                    .iter()
+                    .chain(std::iter::once(&Vec2::ZERO))
+                    .map(|&direction| position + direction)
+                    .find(|&previous| is_set(rows, previous))
+                    .unwrap_or(from);
             }
+            positions.push(position);
         }
-        None
+        positions.reverse();
+        Some(positions)
+    }
+
+    /// The expedition's positions each minute on the quickest journey which
+    /// crosses the valley a number of times, starting at the entrance and
+    /// turning back at each end.
+    fn journey(&self, crossings: usize) -> Option<Vec<Vec2>> {
+        let mut positions = vec![Self::ENTRANCE];
+        let mut ends = [Self::ENTRANCE, self.exit()];
+        for _ in 0..crossings {
+            let crossing = self.cross(ends[0], ends[1], positions.len() - 1)?;
+            positions.extend(&crossing[1..]);
+            ends.swap(0, 1);
+        }
+        Some(positions)
     }
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Inverted for max heap
-        other.lower_bound().cmp(&self.lower_bound())
+impl Visualize for Day24 {
+    fn visualize(&self, recorder: &mut Recorder) {
+        let journey = self.0.journey(3).unwrap();
+        for (time, &position) in journey.iter().enumerate() {
+            recorder.record(self.0.draw(time, position));
+        }
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl Day24 {
+    fn journey_time(&self, crossings: usize) -> usize {
+        self.0.journey(crossings).unwrap().len() - 1
     }
 }
 
@@ -157,28 +212,74 @@ impl Day for Day24 {
     }
 
     fn part1(&self) -> String {
-        let mut valley = self.0.clone();
-        State {
-            position: Vec2::new(0, -1),
-            steps: 0,
-            target: Vec2::new(valley.width as isize - 1, valley.height as isize),
-        }.best_path(&mut valley).unwrap().steps.to_string()
+        self.journey_time(1).to_string()
     }
 
     fn part2(&self) -> String {
-        let mut valley = self.0.clone();
-        let mut state = State {
-            position: Vec2::new(0, -1),
-            steps: 0,
-            target: Vec2::new(valley.width as isize - 1, valley.height as isize),
-        }.best_path(&mut valley).unwrap();
-        state = State {
-            target: Vec2::new(0, -1),
-            ..state
-        }.best_path(&mut valley).unwrap();
-        State {
-            target: Vec2::new(valley.width as isize - 1, valley.height as isize),
-            ..state
-        }.best_path(&mut valley).unwrap().steps.to_string()
+        self.journey_time(3).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hash::FxHashSet;
+
+    const EXAMPLE: &str = "\
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    /// The minutes taken by the quickest journey, found by stepping every
+    /// position the expedition could be in one minute at a time.
+    fn naive_journey(valley: &Valley, crossings: usize) -> usize {
+        let mut ends = [Valley::ENTRANCE, valley.exit()];
+        let mut time = 0;
+        for _ in 0..crossings {
+            let mut positions = FxHashSet::from_iter([ends[0]]);
+            while !positions.contains(&ends[1]) {
+                time += 1;
+                positions = positions
+                    .iter()
+                    .flat_map(|&position| {
+                        CARDINALS
+                            .iter()
+                            .chain([&Vec2::ZERO])
+                            .map(move |&direction| position + direction)
+                    })
+                    .filter(|&position| valley.open(position, time))
+                    .collect();
+            }
+            ends.swap(0, 1);
+        }
+        time
+    }
+
+    #[test]
+    fn test_example() {
+        let day = Day24::parse(EXAMPLE);
+        assert_eq!(day.part1(), "18");
+        assert_eq!(day.part2(), "54");
+
+        let journey = day.0.journey(5).unwrap();
+        assert_eq!(journey.len() - 1, 90);
+        assert_eq!(journey.len() - 1, naive_journey(&day.0, 5));
+        assert_eq!(journey[0], Valley::ENTRANCE);
+        assert_eq!(*journey.last().unwrap(), day.0.exit());
+        for (time, pair) in journey.windows(2).enumerate() {
+            assert!((pair[1] - pair[0]).manhattan() <= 1);
+            assert!(day.0.open(pair[1], time + 1));
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        // The only cell always has a blizzard in it.
+        let valley = Valley::parse("#.#\n#>#\n#.#\n");
+        assert_eq!(valley.journey(1), None);
     }
 }
@@ -1,6 +1,11 @@
-use std::collections::{FxHashMap, VecDeque, BinaryHeap};
+use std::collections::VecDeque;
 
-use crate::Day;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    optimize::{branch_and_bound, BranchAndBound},
+    Day,
+};
 
 #[derive(Clone)]
 pub struct Day16 {
@@ -107,18 +112,6 @@ struct State<const TURNS_PER_AGENT: usize> {
     upper_bound: usize,
 }
 
-impl<const TURNS_PER_AGENT: usize> PartialOrd for State<TURNS_PER_AGENT> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.upper_bound.partial_cmp(&other.upper_bound)
-    }
-}
-
-impl<const TURNS_PER_AGENT: usize> Ord for State<TURNS_PER_AGENT> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.upper_bound.cmp(&other.upper_bound)
-    }
-}
-
 impl<const TURNS_PER_AGENT: usize> State<TURNS_PER_AGENT> {
     fn initial(valves: &Day16, agents: usize) -> Self {
         Self::new(
@@ -203,20 +196,32 @@ impl<const TURNS_PER_AGENT: usize> State<TURNS_PER_AGENT> {
                 }
             )
     }
+}
 
-    fn max_release(self, valves: &Day16) -> usize {
-        let mut queue = BinaryHeap::new();
-        queue.push(self);
-        let mut max_release = 0;
-        while let Some(state) = queue.pop() {
-            max_release = max_release.max(state.total_released);
-            for continuation in state.continuations(valves) {
-                if continuation.upper_bound > max_release {
-                    queue.push(continuation);
-                }
-            }
-        }
-        max_release
+/// Search for the most pressure `agents` can release, one after another.
+struct Search<'a, const TURNS_PER_AGENT: usize> {
+    valves: &'a Day16,
+    agents: usize,
+}
+
+impl<const TURNS_PER_AGENT: usize> BranchAndBound for Search<'_, TURNS_PER_AGENT> {
+    type Node = State<TURNS_PER_AGENT>;
+    type Key = ();
+
+    fn root(&self) -> Self::Node {
+        State::initial(self.valves, self.agents)
+    }
+
+    fn children(&self, state: &Self::Node) -> Vec<Self::Node> {
+        state.continuations(self.valves).collect()
+    }
+
+    fn lower_bound(&self, state: &Self::Node) -> usize {
+        state.total_released
+    }
+
+    fn upper_bound(&self, state: &Self::Node) -> usize {
+        state.upper_bound
     }
 }
 
@@ -226,10 +231,20 @@ impl Day for Day16 {
     }
 
     fn part1(&self) -> String {
-        State::<30>::initial(self, 1).max_release(self).to_string()
+        branch_and_bound(&Search::<30> {
+            valves: self,
+            agents: 1,
+        })
+        .value
+        .to_string()
     }
 
     fn part2(&self) -> String {
-        State::<26>::initial(self, 2).max_release(self).to_string()
+        branch_and_bound(&Search::<26> {
+            valves: self,
+            agents: 2,
+        })
+        .value
+        .to_string()
     }
 }
@@ -0,0 +1,43 @@
+use log::warn;
+
+use crate::{cli::DayPick, data, Day};
+
+/// Implemented by days which can describe how they reached their answers.
+pub trait Explain: Day {
+    /// A minute-by-minute account of the best plan for part 1.
+    fn explain_part1(&self) -> String;
+    /// A minute-by-minute account of the best plan for part 2.
+    fn explain_part2(&self) -> String;
+}
+
+macro_rules! explain_days {
+    ( $pick:expr, $data:expr, { $( $num:literal => $day_mod:ident::$day_type:ident, )* } ) => {
+        match $pick.day {
+            $( $num => explain_day::<crate::days::$day_mod::$day_type>($pick, $data), )*
+            day => warn!("Day {day} has no explanation"),
+        }
+    };
+}
+
+/// Print explanations for each picked part of the days which support it.
+pub fn run(data: &data::All, picks: &[DayPick; 25]) {
+    for pick in picks {
+        if !(pick.part1 || pick.part2) {
+            continue;
+        }
+        explain_days!(pick, data, {
+            16 => day16::Day16,
+            19 => day19::Day19,
+        });
+    }
+}
+
+fn explain_day<D: Explain>(pick: &DayPick, data: &data::All) {
+    let solution = D::parse(data[pick.day - 1].input);
+    if pick.part1 {
+        println!("Day {} part 1\n\n{}", pick.day, solution.explain_part1());
+    }
+    if pick.part2 {
+        println!("Day {} part 2\n\n{}", pick.day, solution.explain_part2());
+    }
+}
@@ -0,0 +1,391 @@
+//! Arithmetic expressions over exact fractions, with at most one unknown.
+
+mod rational;
+
+use std::{fmt, rc::Rc};
+
+use rustc_hash::FxHashMap;
+
+pub use rational::Rational;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator {
+    pub fn parse(c: char) -> Self {
+        match c {
+            '+' => Self::Add,
+            '-' => Self::Sub,
+            '*' => Self::Mul,
+            '/' => Self::Div,
+            _ => panic!("Invalid operator: {c}"),
+        }
+    }
+
+    pub fn apply(self, lhs: Rational, rhs: Rational) -> Rational {
+        match self {
+            Self::Add => lhs + rhs,
+            Self::Sub => lhs - rhs,
+            Self::Mul => lhs * rhs,
+            Self::Div => lhs / rhs,
+        }
+    }
+
+    const fn symbol(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Expr {
+    Const(Rational),
+    Unknown,
+    /// An operation on two subexpressions, which may be shared with other
+    /// expressions.
+    Op(Operator, Rc<Self>, Rc<Self>),
+}
+
+impl Expr {
+    pub fn op(op: Operator, lhs: Self, rhs: Self) -> Self {
+        Self::Op(op, Rc::new(lhs), Rc::new(rhs))
+    }
+
+    /// The value of the expression with `unknown` substituted in.
+    ///
+    /// # Panics
+    ///
+    /// If it divides by zero.
+    pub fn eval(&self, unknown: Rational) -> Rational {
+        match self {
+            Self::Const(value) => *value,
+            Self::Unknown => unknown,
+            Self::Op(op, lhs, rhs) => op.apply(lhs.eval(unknown), rhs.eval(unknown)),
+        }
+    }
+
+    /// Fold constant subexpressions and remove operations which do nothing,
+    /// like adding zero or multiplying by one.
+    pub fn simplify(self) -> Self {
+        let Self::Op(op, lhs, rhs) = self else {
+            return self;
+        };
+        let (lhs, rhs) = (
+            Rc::unwrap_or_clone(lhs).simplify(),
+            Rc::unwrap_or_clone(rhs).simplify(),
+        );
+        let zero = Self::Const(Rational::ZERO);
+        let one = Self::Const(Rational::ONE);
+        match (op, lhs, rhs) {
+            (op, Self::Const(lhs), Self::Const(rhs)) if !(op == Operator::Div && rhs.is_zero()) => {
+                Self::Const(op.apply(lhs, rhs))
+            }
+            (Operator::Add, expr, other) | (Operator::Add, other, expr) if other == zero => expr,
+            (Operator::Sub, expr, other) if other == zero => expr,
+            (Operator::Mul, expr, other) | (Operator::Mul, other, expr) if other == one => expr,
+            (Operator::Div, expr, other) if other == one => expr,
+            (Operator::Mul, _, other) | (Operator::Mul, other, _) if other == zero => zero,
+            (op, lhs, rhs) => Self::op(op, lhs, rhs),
+        }
+    }
+
+    /// The expression as `a * unknown + b`, or `None` if it isn't linear in
+    /// the unknown.
+    pub fn linear(&self) -> Option<Linear> {
+        Some(match self {
+            Self::Const(value) => Linear {
+                coefficient: Rational::ZERO,
+                constant: *value,
+            },
+            Self::Unknown => Linear {
+                coefficient: Rational::ONE,
+                constant: Rational::ZERO,
+            },
+            Self::Op(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.linear()?, rhs.linear()?);
+                match op {
+                    Operator::Add => lhs + rhs,
+                    Operator::Sub => lhs - rhs,
+                    Operator::Mul if lhs.is_constant() => rhs.scale(lhs.constant),
+                    Operator::Mul if rhs.is_constant() => lhs.scale(rhs.constant),
+                    Operator::Div if rhs.is_constant() && !rhs.constant.is_zero() => {
+                        lhs.scale(Rational::ONE / rhs.constant)
+                    }
+                    Operator::Mul | Operator::Div => return None,
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Const(value) => write!(f, "{value}"),
+            Self::Unknown => write!(f, "x"),
+            Self::Op(op, lhs, rhs) => write!(f, "({lhs} {} {rhs})", op.symbol()),
+        }
+    }
+}
+
+/// `coefficient * unknown + constant`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Linear {
+    pub coefficient: Rational,
+    pub constant: Rational,
+}
+
+impl Linear {
+    const fn is_constant(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    fn scale(self, factor: Rational) -> Self {
+        Self {
+            coefficient: self.coefficient * factor,
+            constant: self.constant * factor,
+        }
+    }
+}
+
+impl std::ops::Add for Linear {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            coefficient: self.coefficient + other.coefficient,
+            constant: self.constant + other.constant,
+        }
+    }
+}
+
+impl std::ops::Sub for Linear {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + other.scale(-Rational::ONE)
+    }
+}
+
+/// The value of the unknown which makes both sides equal. Returns `None` if
+/// either side isn't linear, or there isn't exactly one solution.
+pub fn solve(lhs: &Expr, rhs: &Expr) -> Option<Rational> {
+    let difference = lhs.linear()? - rhs.linear()?;
+    (!difference.is_constant()).then(|| -difference.constant / difference.coefficient)
+}
+
+/// Named definitions which refer to each other, like `a: b + c` or `b: 5`.
+pub struct Definitions<'a> {
+    indices: FxHashMap<&'a str, usize>,
+    definitions: Vec<Definition<'a>>,
+}
+
+enum Definition<'a> {
+    Value(Rational),
+    Op(Operator, &'a str, &'a str),
+}
+
+/// How far a definition has been expanded.
+#[derive(Clone)]
+enum Expansion {
+    Unexpanded,
+    InProgress,
+    Done(Expr),
+}
+
+impl<'a> Definitions<'a> {
+    pub fn parse(input: &'a str) -> Self {
+        let mut indices = FxHashMap::default();
+        let mut definitions = Vec::new();
+        for line in input.lines() {
+            let (name, definition) = line.split_once(": ").unwrap();
+            let mut parts = definition.split(' ');
+            let definition = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(value), None, None, None) => {
+                    Definition::Value(Rational::integer(value.parse().unwrap()))
+                }
+                (Some(lhs), Some(op), Some(rhs), None) => {
+                    Definition::Op(Operator::parse(op.chars().next().unwrap()), lhs, rhs)
+                }
+                _ => panic!("Invalid definition: {definition}"),
+            };
+            indices.insert(name, definitions.len());
+            definitions.push(definition);
+        }
+        Self {
+            indices,
+            definitions,
+        }
+    }
+
+    /// Expand a name into an expression, treating `unknown` (if given) as the
+    /// unknown rather than using its definition. Operations on two constants
+    /// are folded as they are expanded, and names used more than once share
+    /// one expansion.
+    ///
+    /// # Errors
+    ///
+    /// If a name is undefined or defined in terms of itself.
+    pub fn expr(&self, name: &str, unknown: Option<&str>) -> Result<Expr, String> {
+        let mut expansions = vec![Expansion::Unexpanded; self.definitions.len()];
+        self.expand(name, unknown, &mut expansions)
+    }
+
+    fn expand(
+        &self,
+        name: &str,
+        unknown: Option<&str>,
+        expansions: &mut [Expansion],
+    ) -> Result<Expr, String> {
+        if Some(name) == unknown {
+            return Ok(Expr::Unknown);
+        }
+        let &index = self
+            .indices
+            .get(name)
+            .ok_or_else(|| format!("{name} is not defined"))?;
+        match &expansions[index] {
+            Expansion::Unexpanded => expansions[index] = Expansion::InProgress,
+            Expansion::InProgress => return Err(format!("{name} is defined in terms of itself")),
+            Expansion::Done(expr) => return Ok(expr.clone()),
+        }
+        let expr = match self.definitions[index] {
+            Definition::Value(value) => Expr::Const(value),
+            Definition::Op(op, lhs, rhs) => match (
+                self.expand(lhs, unknown, expansions)?,
+                self.expand(rhs, unknown, expansions)?,
+            ) {
+                (Expr::Const(lhs), Expr::Const(rhs)) if !(op == Operator::Div && rhs.is_zero()) => {
+                    Expr::Const(op.apply(lhs, rhs))
+                }
+                (lhs, rhs) => Expr::op(op, lhs, rhs),
+            },
+        };
+        expansions[index] = Expansion::Done(expr.clone());
+        Ok(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_rational() {
+        let half = Rational::new(2, -4);
+        assert_eq!(half, Rational::new(-1, 2));
+        assert_eq!((half * Rational::integer(-4)).to_integer(), Some(2));
+        assert_eq!((half + Rational::new(1, 3)).to_string(), "-1/6");
+        // Neither of these overflows, even though the product of the
+        // denominators would.
+        let tiny = Rational::new(1, 1 << 100);
+        assert_eq!(tiny + tiny, Rational::new(1, 1 << 99));
+        assert_eq!(
+            Rational::new(1 << 100, 3) * Rational::new(3, 1 << 100),
+            Rational::ONE
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let definitions = Definitions::parse(
+            "\
+left: x * two
+right: y + three
+x: 1
+y: x / two
+two: 2
+three: 3",
+        );
+        let expr = |name| definitions.expr(name, Some("x")).unwrap();
+        assert_eq!(expr("right").to_string(), "((x / 2) + 3)");
+        assert_eq!(
+            expr("left").eval(Rational::integer(5)),
+            Rational::integer(10)
+        );
+        // 2x = x/2 + 3
+        assert_eq!(
+            solve(&expr("left"), &expr("right")),
+            Some(Rational::integer(2))
+        );
+        assert_eq!(solve(&expr("left"), &expr("left")), None);
+        let square = Expr::op(Operator::Mul, Expr::Unknown, Expr::Unknown);
+        assert_eq!(solve(&square, &expr("right")), None);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let definitions = Definitions::parse(
+            "\
+a: b * c
+b: x + zero
+c: one + zero
+x: 7
+zero: 0
+one: 1",
+        );
+        assert_eq!(
+            definitions.expr("a", Some("x")).unwrap().simplify(),
+            Expr::Unknown
+        );
+        assert_eq!(
+            definitions.expr("a", None).unwrap().simplify(),
+            Expr::Const(Rational::integer(7))
+        );
+    }
+
+    #[test]
+    fn test_shared_and_cyclic() {
+        // Each name is used twice, so expanding every use separately would
+        // take 2^40 steps.
+        let mut input = String::from("n0: 1");
+        for i in 1..=40 {
+            write!(input, "\nn{i}: n{} + n{}", i - 1, i - 1).unwrap();
+        }
+        let definitions = Definitions::parse(&input);
+        let expr = definitions.expr("n40", Some("n0")).unwrap();
+        let Expr::Op(_, lhs, rhs) = &expr else {
+            panic!("n40 should be an operation");
+        };
+        let (Expr::Op(_, lhs, _), Expr::Op(_, rhs, _)) = (&**lhs, &**rhs) else {
+            panic!("n39 should be an operation");
+        };
+        assert!(Rc::ptr_eq(lhs, rhs));
+        assert_eq!(
+            definitions
+                .expr("n10", Some("n0"))
+                .unwrap()
+                .eval(Rational::ONE),
+            Rational::integer(1 << 10)
+        );
+
+        let definitions = Definitions::parse("a: b + c\nb: c * a\nc: 1");
+        assert_eq!(
+            definitions.expr("a", None),
+            Err("a is defined in terms of itself".to_string())
+        );
+        assert_eq!(
+            definitions.expr("a", Some("b")),
+            Ok(Expr::op(
+                Operator::Add,
+                Expr::Unknown,
+                Expr::Const(Rational::ONE)
+            ))
+        );
+        assert_eq!(
+            Definitions::parse("a: b + c\nb: 1").expr("a", None),
+            Err("c is not defined".to_string())
+        );
+    }
+}
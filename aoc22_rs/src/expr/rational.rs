@@ -0,0 +1,136 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+/// An exact fraction, always stored in lowest terms with a positive
+/// denominator.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+const fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+impl Rational {
+    pub const ZERO: Self = Self::integer(0);
+    pub const ONE: Self = Self::integer(1);
+
+    /// # Panics
+    ///
+    /// If the denominator is zero.
+    pub const fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator");
+        let divisor = gcd(num, den) * den.signum();
+        Self {
+            num: num / divisor,
+            den: den / divisor,
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Self { num: value, den: 1 }
+    }
+
+    /// The value as an integer, if it is one.
+    #[cfg(test)]
+    pub const fn to_integer(self) -> Option<i128> {
+        if self.den == 1 {
+            Some(self.num)
+        } else {
+            None
+        }
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.num == 0
+    }
+}
+
+impl From<isize> for Rational {
+    fn from(value: isize) -> Self {
+        Self::integer(value as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        if self.den == 1 && other.den == 1 {
+            return Self::integer(self.num + other.num);
+        }
+        // Scale both to the lowest common denominator rather than the product
+        // of the denominators, so deep chains of operations don't overflow.
+        let divisor = gcd(self.den, other.den);
+        Self::new(
+            self.num * (other.den / divisor) + other.num * (self.den / divisor),
+            self.den * (other.den / divisor),
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        if self.den == 1 && other.den == 1 {
+            return Self::integer(self.num * other.num);
+        }
+        // Cancel common factors before multiplying, so the products stay small.
+        let (a, b) = (gcd(self.num, other.den), gcd(other.num, self.den));
+        Self::new(
+            (self.num / a) * (other.num / b),
+            (self.den / b) * (other.den / a),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// If `other` is zero.
+    fn div(self, other: Self) -> Self {
+        if self.den == 1 && other.den == 1 && other.num != 0 && self.num % other.num == 0 {
+            return Self::integer(self.num / other.num);
+        }
+        Self::new(self.num * other.den, self.den * other.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use crate::vec2::{Vec2, CARDINALS, DIAGONALS};
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid of cells, stored row by row, indexed by [`Vec2`] with
+/// `(0, 0)` at the top left.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Vec2) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Vec2::new(x as isize, y as isize)))
+            .map(&mut f)
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Parse a map with one character per cell. All lines must be the same length.
+    pub fn parse(input: &str, parse_cell: impl FnMut(char) -> T) -> Self {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        assert!(
+            input.lines().all(|line| line.chars().count() == width),
+            "lines in grid have different lengths"
+        );
+        Self::parse_padded(input, ' ', parse_cell)
+    }
+
+    /// Parse a map with one character per cell, padding short lines on the
+    /// right with `padding`.
+    pub fn parse_padded(input: &str, padding: char, mut parse_cell: impl FnMut(char) -> T) -> Self {
+        let width = input
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cells = Vec::new();
+        let mut height = 0;
+        for line in input.lines() {
+            cells.extend(
+                line.chars()
+                    .chain(std::iter::repeat(padding))
+                    .take(width)
+                    .map(&mut parse_cell),
+            );
+            height += 1;
+        }
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        (0..self.width as isize).contains(&pos.x()) && (0..self.height as isize).contains(&pos.y())
+    }
+
+    /// The index of a position when cells are numbered row by row, or `None`
+    /// if it is outside the grid.
+    pub fn offset(&self, pos: Vec2) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.y() as usize * self.width + pos.x() as usize)
+    }
+
+    /// The cell at a position, or `None` if it is outside the grid.
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.offset(pos).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.offset(pos).map(|offset| &mut self.cells[offset])
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let width = self.width as isize;
+        (0..self.height as isize).flat_map(move |y| (0..width).map(move |x| Vec2::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The position of the first cell (row by row) matching a predicate.
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Vec2> {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    fn neighbours_in(
+        &self,
+        pos: Vec2,
+        directions: &'static [Vec2],
+    ) -> impl Iterator<Item = Vec2> + '_ {
+        directions
+            .iter()
+            .map(move |&direction| pos + direction)
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// Positions above, below, left and right of a position which are in the grid.
+    pub fn cardinal_neighbours(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        self.neighbours_in(pos, &CARDINALS)
+    }
+
+    /// Positions diagonally adjacent to a position which are in the grid.
+    #[allow(dead_code)]
+    pub fn diagonal_neighbours(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        self.neighbours_in(pos, &DIAGONALS)
+    }
+
+    /// All eight positions surrounding a position which are in the grid.
+    #[allow(dead_code)]
+    pub fn neighbours(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        pos.neighbours()
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    #[allow(dead_code)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of range");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(&mut f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+// No solution needs to turn its map round yet.
+#[allow(dead_code)]
+impl<T: Clone> Grid<T> {
+    /// Flip the grid along its leading diagonal, so rows become columns.
+    #[must_use]
+    pub fn transposed(&self) -> Self {
+        Self::from_fn(self.height, self.width, |pos| {
+            self[Vec2::new(pos.y(), pos.x())].clone()
+        })
+    }
+
+    #[must_use]
+    pub fn rotated_clockwise(&self) -> Self {
+        let max_y = self.height as isize - 1;
+        Self::from_fn(self.height, self.width, |pos| {
+            self[Vec2::new(pos.y(), max_y - pos.x())].clone()
+        })
+    }
+
+    #[must_use]
+    pub fn rotated_anticlockwise(&self) -> Self {
+        let max_x = self.width as isize - 1;
+        Self::from_fn(self.height, self.width, |pos| {
+            self[Vec2::new(max_x - pos.y(), pos.x())].clone()
+        })
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> std::fmt::Debug for Grid<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.rows()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap())
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Vec2::new(2, 1)), Some(&6));
+        assert_eq!(grid.get(Vec2::new(-1, 0)), None);
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        assert_eq!(grid.find(|&cell| cell == 5), Some(Vec2::new(1, 1)));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits();
+        let values = |positions: Vec<Vec2>| -> Vec<u32> {
+            let mut values: Vec<_> = positions.into_iter().map(|pos| grid[pos]).collect();
+            values.sort_unstable();
+            values
+        };
+        let corner = Vec2::new(0, 0);
+        assert_eq!(values(grid.cardinal_neighbours(corner).collect()), [2, 4]);
+        assert_eq!(values(grid.diagonal_neighbours(corner).collect()), [5]);
+        assert_eq!(
+            values(grid.neighbours(Vec2::new(1, 0)).collect()),
+            [1, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = digits();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = digits();
+        assert_eq!(grid.transposed().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotated_clockwise().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotated_anticlockwise().to_string(), "36\n25\n14\n");
+        assert_eq!(grid.rotated_clockwise().rotated_anticlockwise(), grid);
+    }
+
+    #[test]
+    fn test_parse_padded() {
+        let grid = Grid::parse_padded("ab\na\n", '.', |c| c);
+        assert_eq!(grid.to_string(), "ab\na.\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "lines in grid have different lengths")]
+    fn test_parse_uneven() {
+        Grid::parse("ab\na\n", |c| c);
+    }
+}
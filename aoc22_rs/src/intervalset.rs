@@ -0,0 +1,174 @@
+use std::{
+    iter::Sum,
+    ops::{Range, Sub},
+};
+
+/// A set of values stored as sorted, disjoint, half-open ranges. Touching
+/// ranges are merged, so each range is as long as possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    #[allow(dead_code)]
+    pub const fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= value)
+    }
+
+    /// Add every value in a range.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges between these overlap or touch the new one.
+        let first = self.ranges.partition_point(|other| other.end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|other| other.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Remove every value in a range.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // Ranges between these overlap the removed one.
+        let first = self
+            .ranges
+            .partition_point(|other| other.end <= range.start);
+        let last = self.ranges.partition_point(|other| other.start < range.end);
+        if first == last {
+            return;
+        }
+        let (start, end) = (self.ranges[first].start, self.ranges[last - 1].end);
+        let left = (start < range.start).then_some(start..range.start);
+        let right = (range.end < end).then_some(range.end..end);
+        self.ranges
+            .splice(first..last, left.into_iter().chain(right));
+    }
+
+    #[allow(dead_code)]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    #[allow(dead_code)]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let (start, end) = (x.start.max(y.start), x.end.min(y.end));
+            if start < end {
+                ranges.push(start..end);
+            }
+            // Drop whichever range finishes first, as it can't overlap anything
+            // else in the other set.
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /// The ranges within `bounds` which aren't in the set.
+    #[allow(dead_code)]
+    pub fn gaps(&self, bounds: Range<T>) -> Vec<Range<T>> {
+        let mut gaps = Self::new();
+        gaps.insert(bounds);
+        gaps.difference(self).ranges
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Sum> IntervalSet<T> {
+    /// The number of values in the set.
+    pub fn total_length(&self) -> T {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set: IntervalSet<i32> = [0..3, 10..12, 5..6, 3..4].into_iter().collect();
+        assert_eq!(set.ranges(), [0..4, 5..6, 10..12]);
+        set.insert(2..11);
+        assert_eq!(set, IntervalSet::from_iter(Some(0..12)));
+        set.remove(4..6);
+        set.remove(20..30);
+        assert_eq!(set.ranges(), [0..4, 6..12]);
+        assert!(set.contains(3) && !set.contains(4) && set.contains(6));
+        assert_eq!(set.total_length(), 10);
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a: IntervalSet<i32> = [0..5, 10..15].into_iter().collect();
+        let b: IntervalSet<i32> = [3..12, 14..20].into_iter().collect();
+        assert_eq!(a.union(&b), IntervalSet::from_iter(Some(0..20)));
+        assert_eq!(a.intersection(&b).ranges(), [3..5, 10..12, 14..15]);
+        assert_eq!(a.difference(&b).ranges(), [0..3, 12..14]);
+        assert_eq!(a.gaps(-5..17), [-5..0, 5..10, 15..17]);
+    }
+}
@@ -13,15 +13,32 @@
 )]
 #![feature(const_option)]
 #![feature(const_for)]
+#![cfg_attr(test, feature(test))]
+
+#[cfg(test)]
+extern crate test;
 
 mod cli;
+mod cube;
+mod cycle;
 mod data;
 mod days;
+mod explain;
+mod expr;
+mod grid;
+mod intervalset;
+mod optimize;
 mod output;
+mod pointset;
+mod report;
 mod runner;
+mod search;
 mod timer;
+mod tui;
 mod util;
 mod vec2;
+mod visualize;
+mod watch;
 
 trait Day {
     fn parse(input: &'static str) -> Self
@@ -32,8 +49,66 @@ trait Day {
 }
 
 fn main() {
-    let picks = cli::Args::get_picks();
-    let data = data::load();
-    let timings = runner::run(data, &picks);
-    output::print_timings(&timings);
+    let options = cli::Args::get_options();
+    match options.command {
+        cli::Command::Run {
+            tui,
+            json,
+            report,
+            variants,
+        } => {
+            let data = data::load();
+            let timings = if tui {
+                tui::run(data, &options.picks, options.style, variants)
+            } else {
+                runner::run(data, &options.picks, &variants)
+            };
+            if json {
+                println!("{}", serde_json::to_string(&timings).unwrap());
+            } else {
+                output::print_timings(&timings, &options.table, options.style);
+            }
+            if let Some(dir) = report {
+                report::write(&timings, &dir, options.style.palette).unwrap();
+            }
+        }
+        cli::Command::Visualize(visualize) => {
+            visualize::run(&data::load(), &options.picks, &visualize).unwrap();
+        }
+        cli::Command::Explain => explain::run(&data::load(), &options.picks),
+        cli::Command::Valves {
+            input,
+            scenario,
+            method,
+        } => {
+            let default_input = data::load()[15].input;
+            days::day16::run_scenario(input.as_deref(), default_input, &scenario, method)
+                .unwrap_or_else(|err| {
+                    eprintln!("error: {err}");
+                    std::process::exit(1);
+                });
+        }
+        cli::Command::Blocks {
+            input,
+            blocks,
+            width,
+            count,
+        } => {
+            let default_input = data::load()[16].input;
+            days::day17::run_blocks(
+                input.as_deref(),
+                default_input,
+                blocks.as_deref(),
+                width,
+                count,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                std::process::exit(1);
+            });
+        }
+        cli::Command::Watch { variants } => {
+            watch::run(&options.picks, &variants, &options.table, options.style);
+        }
+    }
 }
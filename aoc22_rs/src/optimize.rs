@@ -0,0 +1,294 @@
+use std::{cmp::Ordering, collections::BinaryHeap, fmt, hash::Hash};
+
+use rustc_hash::FxHashMap;
+
+/// A maximisation problem explored as a tree of partial solutions.
+pub trait BranchAndBound {
+    type Node: Clone;
+    /// Identifies nodes which can be compared with [`Self::dominates`].
+    type Key: Eq + Hash;
+
+    fn root(&self) -> Self::Node;
+    fn children(&self, node: &Self::Node) -> Vec<Self::Node>;
+    /// A value which can definitely be reached from a node. Nodes are explored
+    /// in decreasing order of this.
+    fn lower_bound(&self, node: &Self::Node) -> usize;
+    /// A value which can't be beaten from a node. Nodes whose upper bound is
+    /// no better than the best value found are pruned.
+    fn upper_bound(&self, node: &Self::Node) -> usize;
+
+    /// The value of stopping at a node. Defaults to the lower bound.
+    fn value(&self, node: &Self::Node) -> usize {
+        self.lower_bound(node)
+    }
+
+    /// The key to remember a node by, if it should be checked against others.
+    fn key(&self, _node: &Self::Node) -> Option<Self::Key> {
+        None
+    }
+
+    /// Whether `node` is at least as good as `other`, which has the same key,
+    /// so `other` needn't be explored. Defaults to true, which just skips
+    /// repeated keys.
+    fn dominates(&self, _node: &Self::Node, _other: &Self::Node) -> bool {
+        true
+    }
+}
+
+/// The best node found by [`branch_and_bound`].
+#[derive(Clone, Debug)]
+pub struct Solution<N> {
+    pub value: usize,
+    pub node: N,
+    pub stats: Stats,
+}
+
+/// Counts of what happened during a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub expanded: usize,
+    pub pruned_by_bound: usize,
+    pub pruned_by_dominance: usize,
+    pub max_queue: usize,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expanded {} nodes, pruned {} by bound and {} by dominance, queue peaked at {}",
+            self.expanded, self.pruned_by_bound, self.pruned_by_dominance, self.max_queue
+        )
+    }
+}
+
+struct Entry<N> {
+    lower_bound: usize,
+    upper_bound: usize,
+    node: N,
+    /// The index of the node's parent in the trail, when tracing.
+    parent: Option<usize>,
+}
+
+impl<N> PartialEq for Entry<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lower_bound == other.lower_bound
+    }
+}
+
+impl<N> Eq for Entry<N> {}
+
+impl<N> PartialOrd for Entry<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Entry<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lower_bound.cmp(&other.lower_bound)
+    }
+}
+
+/// Find the node with the highest value, exploring the most promising nodes
+/// first and skipping any which can't beat the best found so far.
+pub fn branch_and_bound<P: BranchAndBound>(problem: &P) -> Solution<P::Node> {
+    search(problem, false).0
+}
+
+/// Like [`branch_and_bound`], but also returns the nodes leading from the
+/// root to the best node, inclusive. This keeps every expanded node, so it
+/// uses more memory.
+pub fn branch_and_bound_path<P: BranchAndBound>(problem: &P) -> (Solution<P::Node>, Vec<P::Node>) {
+    search(problem, true)
+}
+
+fn search<P: BranchAndBound>(problem: &P, trace: bool) -> (Solution<P::Node>, Vec<P::Node>) {
+    let root = problem.root();
+    let mut best = Solution {
+        value: problem.value(&root),
+        node: root.clone(),
+        stats: Stats::default(),
+    };
+    let mut stats = Stats::default();
+    // Expanded nodes and their parents' indices, when tracing.
+    let mut trail: Vec<(P::Node, Option<usize>)> = Vec::new();
+    let mut best_index = None;
+    let mut seen: FxHashMap<P::Key, Vec<P::Node>> = FxHashMap::default();
+    let mut queue = BinaryHeap::from([Entry {
+        lower_bound: problem.lower_bound(&root),
+        upper_bound: problem.upper_bound(&root),
+        node: root,
+        parent: None,
+    }]);
+    while let Some(entry) = queue.pop() {
+        if entry.upper_bound <= best.value {
+            stats.pruned_by_bound += 1;
+            continue;
+        }
+        stats.expanded += 1;
+        let index = trace.then(|| {
+            trail.push((entry.node.clone(), entry.parent));
+            trail.len() - 1
+        });
+        let value = problem.value(&entry.node);
+        if value > best.value {
+            best.value = value;
+            best.node = entry.node.clone();
+            best_index = index;
+        }
+        for child in problem.children(&entry.node) {
+            let upper_bound = problem.upper_bound(&child);
+            if upper_bound <= best.value {
+                stats.pruned_by_bound += 1;
+                continue;
+            }
+            if let Some(key) = problem.key(&child) {
+                let others = seen.entry(key).or_default();
+                if others.iter().any(|other| problem.dominates(other, &child)) {
+                    stats.pruned_by_dominance += 1;
+                    continue;
+                }
+                others.retain(|other| !problem.dominates(&child, other));
+                others.push(child.clone());
+            }
+            queue.push(Entry {
+                lower_bound: problem.lower_bound(&child),
+                upper_bound,
+                node: child,
+                parent: index,
+            });
+        }
+        stats.max_queue = stats.max_queue.max(queue.len());
+    }
+    best.stats = stats;
+
+    let mut path = Vec::new();
+    if trace {
+        // The root is the best node if nothing beat its value.
+        let mut index = best_index.or_else(|| (!trail.is_empty()).then_some(0));
+        while let Some(i) = index {
+            let (node, parent) = &trail[i];
+            path.push(node.clone());
+            index = *parent;
+        }
+        if path.is_empty() {
+            path.push(best.node.clone());
+        }
+        path.reverse();
+    }
+    (best, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0/1 knapsack: each node is the items decided so far and their total
+    /// weight and value.
+    struct Knapsack {
+        items: Vec<(usize, usize)>,
+        capacity: usize,
+        memoize: bool,
+    }
+
+    #[derive(Clone, Debug)]
+    struct Packing {
+        next: usize,
+        weight: usize,
+        value: usize,
+    }
+
+    impl BranchAndBound for Knapsack {
+        type Node = Packing;
+        type Key = (usize, usize);
+
+        fn root(&self) -> Packing {
+            Packing {
+                next: 0,
+                weight: 0,
+                value: 0,
+            }
+        }
+
+        fn children(&self, node: &Packing) -> Vec<Packing> {
+            let Some(&(weight, value)) = self.items.get(node.next) else {
+                return Vec::new();
+            };
+            let skip = Packing {
+                next: node.next + 1,
+                ..*node
+            };
+            let take = Packing {
+                next: node.next + 1,
+                weight: node.weight + weight,
+                value: node.value + value,
+            };
+            if take.weight <= self.capacity {
+                vec![skip, take]
+            } else {
+                vec![skip]
+            }
+        }
+
+        fn lower_bound(&self, node: &Packing) -> usize {
+            node.value
+        }
+
+        fn upper_bound(&self, node: &Packing) -> usize {
+            node.value
+                + self.items[node.next..]
+                    .iter()
+                    .map(|(_, v)| v)
+                    .sum::<usize>()
+        }
+
+        fn key(&self, node: &Packing) -> Option<(usize, usize)> {
+            self.memoize.then_some((node.next, node.weight))
+        }
+
+        fn dominates(&self, node: &Packing, other: &Packing) -> bool {
+            node.value >= other.value
+        }
+    }
+
+    #[test]
+    fn test_knapsack() {
+        let items = vec![
+            (5, 10),
+            (4, 40),
+            (6, 30),
+            (3, 50),
+            (2, 5),
+            (2, 5),
+            (1, 2),
+            (1, 2),
+            (1, 1),
+            (1, 1),
+        ];
+        let plain = branch_and_bound(&Knapsack {
+            items: items.clone(),
+            capacity: 10,
+            memoize: false,
+        });
+        assert_eq!(plain.value, 97);
+        assert_eq!(plain.node.weight, 10);
+        assert_eq!(plain.stats.pruned_by_dominance, 0);
+        let memoized = branch_and_bound(&Knapsack {
+            items: items.clone(),
+            capacity: 10,
+            memoize: true,
+        });
+        assert_eq!(memoized.value, 97);
+        assert!(memoized.stats.pruned_by_dominance > 0);
+        let (traced, path) = branch_and_bound_path(&Knapsack {
+            items,
+            capacity: 10,
+            memoize: true,
+        });
+        assert_eq!(traced.value, 97);
+        assert_eq!(path.first().unwrap().next, 0);
+        assert_eq!(path.last().unwrap().value, 97);
+        assert!(path.windows(2).all(|pair| pair[1].next == pair[0].next + 1));
+    }
+}
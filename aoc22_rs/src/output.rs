@@ -1,142 +1,465 @@
 use crate::runner::{DayTimings, WrongAnswer};
-use std::time::Duration;
-
-const DAY_WIDTH: usize = 5;
-const TIMING_WIDTH: usize = 15;
-
-pub fn print_timings(timings: &[DayTimings]) {
-    Row::print_top();
-    Row {
-        day: "Day".into(),
-        parse: "Parse".into(),
-        part1: "Part 1".into(),
-        part2: "Part 2".into(),
-        total: "Total".into(),
-    }
-    .print();
-    Row::print_sep();
-    let mut parse_total = Duration::ZERO;
-    let mut part1_total = Duration::ZERO;
-    let mut part2_total = Duration::ZERO;
-    for timing in timings {
-        let parse_time = timing
-            .parse
-            .as_ref()
-            .map_or(Duration::ZERO, |timing| *timing);
-        let part1_time = match &timing.part1 {
-            Some(Ok(timing)) => *timing,
-            _ => Duration::ZERO,
-        };
-        let part2_time = match &timing.part2 {
-            Some(Ok(timing)) => *timing,
-            _ => Duration::ZERO,
+use std::{io::IsTerminal, time::Duration};
+
+/// Parts taking at least this long are marked as slow when using symbols.
+const SLOW: Duration = Duration::from_millis(100);
+const SELECTED: Colour = Colour {
+    r: 80,
+    g: 80,
+    b: 80,
+};
+
+/// How timings should be displayed: which colours to use, how to encode them,
+/// and whether to mark slow or wrong parts with symbols.
+#[derive(Clone, Copy, Debug)]
+pub struct Style {
+    pub depth: ColourDepth,
+    pub palette: Palette,
+    pub symbols: bool,
+}
+
+impl Style {
+    /// Work out how to display colours on stdout. `force` is `Some(true)` to
+    /// always use colour, `Some(false)` to never use colour, or `None` to use
+    /// colour if stdout is a terminal and `NO_COLOR` is not set.
+    ///
+    /// Symbols are always used when there is no colour.
+    pub fn detect(force: Option<bool>, palette: Palette, symbols: bool) -> Self {
+        let colour = force.unwrap_or_else(|| {
+            std::io::stdout().is_terminal()
+                && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+        });
+        let depth = if colour {
+            ColourDepth::detect()
+        } else {
+            ColourDepth::None
         };
-        parse_total += parse_time;
-        part1_total += part1_time;
-        part2_total += part2_time;
-        let total = parse_time + part1_time + part2_time;
-        Row {
-            day: timing.day.to_string().into(),
-            parse: format_timing(&timing.parse.map(Ok)),
-            part1: format_timing(&timing.part1),
-            part2: format_timing(&timing.part2),
-            total: format!("{total:?}").into(),
-        }
-        .print();
-    }
-    Row::print_sep();
-    Row {
-        day: "Total".into(),
-        parse: format!("{parse_total:?}").into(),
-        part1: format!("{part1_total:?}").into(),
-        part2: format!("{part2_total:?}").into(),
-        total: format!("{:?}", parse_total + part1_total + part2_total).into(),
-    }
-    .print();
-    Row::print_bottom();
-}
-
-fn format_timing(timing: &Option<Result<Duration, WrongAnswer>>) -> Cell {
+        Self {
+            depth,
+            palette,
+            symbols: symbols || depth == ColourDepth::None,
+        }
+    }
+}
+
+/// The escape codes supported by the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColourDepth {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColour,
+}
+
+impl ColourDepth {
+    fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let colorterm = var("COLORTERM");
+        let term = var("TERM");
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            Self::TrueColour
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else if term == "dumb" {
+            Self::None
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// The SGR parameters to set the given colour, for the foreground (`base`
+    /// 30) or background (`base` 40).
+    fn sgr(self, colour: Colour, base: u8) -> Option<String> {
+        let Colour { r, g, b } = colour;
+        match self {
+            Self::None => None,
+            Self::TrueColour => Some(format!("{};2;{r};{g};{b}", base + 8)),
+            Self::Ansi256 => Some(format!("{};5;{}", base + 8, colour.ansi256())),
+            Self::Ansi16 => {
+                let (index, bright) = colour.ansi16();
+                let base = if bright { base + 60 } else { base };
+                Some((base + index).to_string())
+            }
+        }
+    }
+}
+
+/// The colours used for timings and wrong answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Palette {
+    /// Green for fast parts through yellow to red for slow ones.
+    Traffic,
+    /// Blue for fast parts through green to yellow for slow ones, which can be
+    /// told apart with any type of colour blindness.
+    Viridis,
+    /// Shades of grey, getting brighter for slower parts.
+    Mono,
+}
+
+impl Palette {
+    pub fn timing(self, timing: Duration) -> Colour {
+        match self {
+            Self::Traffic => timing_colour(timing),
+            Self::Viridis => gradient(
+                &[
+                    Colour {
+                        r: 59,
+                        g: 82,
+                        b: 139,
+                    },
+                    Colour {
+                        r: 33,
+                        g: 145,
+                        b: 140,
+                    },
+                    Colour {
+                        r: 94,
+                        g: 201,
+                        b: 98,
+                    },
+                    Colour {
+                        r: 253,
+                        g: 231,
+                        b: 37,
+                    },
+                ],
+                timing_position(timing),
+            ),
+            Self::Mono => gradient(
+                &[
+                    Colour {
+                        r: 96,
+                        g: 96,
+                        b: 96,
+                    },
+                    Colour {
+                        r: 255,
+                        g: 255,
+                        b: 255,
+                    },
+                ],
+                timing_position(timing),
+            ),
+        }
+    }
+
+    pub const fn wrong(self) -> Colour {
+        match self {
+            Self::Traffic => Colour { r: 255, g: 0, b: 0 },
+            Self::Viridis => Colour {
+                r: 204,
+                g: 121,
+                b: 167,
+            },
+            Self::Mono => Colour {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+        }
+    }
+}
+
+/// A column which can be shown in the timing table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    Day,
+    Parse,
+    Part1,
+    Part2,
+    Total,
+    /// The answer given by part 1.
+    Answer1,
+    /// The answer given by part 2.
+    Answer2,
+    /// The day's percentage of the total runtime.
+    Share,
+    /// How many times faster the day is than in the baseline run.
+    Speedup,
+}
+
+impl Column {
+    pub const DEFAULT: [Self; 5] = [
+        Self::Day,
+        Self::Parse,
+        Self::Part1,
+        Self::Part2,
+        Self::Total,
+    ];
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Day => "Day",
+            Self::Parse => "Parse",
+            Self::Part1 => "Part 1",
+            Self::Part2 => "Part 2",
+            Self::Total => "Total",
+            Self::Answer1 => "Answer 1",
+            Self::Answer2 => "Answer 2",
+            Self::Share => "Share",
+            Self::Speedup => "Speedup",
+        }
+    }
+}
+
+/// Which columns to show in the timing table, and the timings to compare
+/// against for the speedup column.
+pub struct TableOptions {
+    pub columns: Vec<Column>,
+    pub baseline: Vec<DayTimings>,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            columns: Column::DEFAULT.to_vec(),
+            baseline: Vec::new(),
+        }
+    }
+}
+
+pub fn print_timings(timings: &[DayTimings], options: &TableOptions, style: Style) {
+    let grand_total: Duration = timings.iter().map(DayTimings::total).sum();
+    let mut table = Table::new(&options.columns);
+    let mut totals = [Duration::ZERO; 3];
+    let mut baseline_total = Duration::ZERO;
+    let mut compared_total = Duration::ZERO;
+    for timing in timings {
+        let durations = timing.durations();
+        for (total, duration) in totals.iter_mut().zip(durations) {
+            *total += duration;
+        }
+        let total = timing.total();
+        let baseline = options
+            .baseline
+            .iter()
+            .find(|baseline| baseline.day == timing.day)
+            .map(DayTimings::total);
+        if let Some(baseline) = baseline {
+            baseline_total += baseline;
+            compared_total += total;
+        }
+        table.push(
+            options
+                .columns
+                .iter()
+                .map(|column| match column {
+                    Column::Day => timing.day.to_string().into(),
+                    Column::Parse => format_timing(&timing.parse.map(Ok), style),
+                    Column::Part1 => format_timing(&timing.part1, style),
+                    Column::Part2 => format_timing(&timing.part2, style),
+                    Column::Total => format!("{total:?}").into(),
+                    Column::Answer1 => format_answer(timing.answers[0].as_deref()),
+                    Column::Answer2 => format_answer(timing.answers[1].as_deref()),
+                    Column::Share => format_share(total, grand_total),
+                    Column::Speedup => format_speedup(baseline, total),
+                })
+                .collect(),
+        );
+    }
+    table.set_footer(
+        options
+            .columns
+            .iter()
+            .map(|column| match column {
+                Column::Day => "Total".into(),
+                Column::Parse => format!("{:?}", totals[0]).into(),
+                Column::Part1 => format!("{:?}", totals[1]).into(),
+                Column::Part2 => format!("{:?}", totals[2]).into(),
+                Column::Total => format!("{grand_total:?}").into(),
+                Column::Answer1 | Column::Answer2 => "".into(),
+                Column::Share => format_share(grand_total, grand_total),
+                Column::Speedup => format_speedup(
+                    Some(baseline_total).filter(|total| !total.is_zero()),
+                    compared_total,
+                ),
+            })
+            .collect(),
+    );
+    for line in table.render(style) {
+        println!("{line}");
+    }
+    if style.symbols {
+        println!("! slow (at least {SLOW:?}), ✗ wrong answer");
+    }
+}
+
+fn format_answer(answer: Option<&str>) -> Cell {
+    answer.unwrap_or("-").into()
+}
+
+fn format_share(timing: Duration, total: Duration) -> Cell {
+    if total.is_zero() {
+        return "-".into();
+    }
+    format!("{:.1}%", timing.as_secs_f64() / total.as_secs_f64() * 100.0).into()
+}
+
+fn format_speedup(baseline: Option<Duration>, timing: Duration) -> Cell {
+    match baseline {
+        Some(baseline) if !timing.is_zero() => {
+            format!("{:.2}×", baseline.as_secs_f64() / timing.as_secs_f64()).into()
+        }
+        _ => "-".into(),
+    }
+}
+
+pub fn format_timing(timing: &Option<Result<Duration, WrongAnswer>>, style: Style) -> Cell {
     match timing {
-        Some(Ok(timing)) => Cell::from(format!("{timing:?}")).fg(timing_colour(*timing)),
-        Some(Err(_)) => Cell::from("Wrong!")
-            .bg(Colour { r: 255, g: 0, b: 0 })
-            .fg(Colour { r: 0, g: 0, b: 0 }),
+        Some(Ok(timing)) => {
+            let text = if style.symbols && *timing >= SLOW {
+                format!("{timing:?} !")
+            } else {
+                format!("{timing:?}")
+            };
+            Cell::from(text).fg(style.palette.timing(*timing))
+        }
+        Some(Err(_)) => Cell::from(if style.symbols {
+            "✗ Wrong!"
+        } else {
+            "Wrong!"
+        })
+        .bg(style.palette.wrong())
+        .fg(Colour { r: 0, g: 0, b: 0 }),
         None => Cell::from("-"),
     }
 }
 
-struct Cell {
+pub struct Cell {
     text: String,
     fg: Option<Colour>,
     bg: Option<Colour>,
 }
 
-struct Colour {
-    r: u8,
-    g: u8,
-    b: u8,
+#[derive(Clone, Copy, Debug)]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Colour {
+    pub fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// The closest colour in the 6x6x6 cube of the 256 colour palette.
+    fn ansi256(self) -> u8 {
+        let level = |channel: u8| (u16::from(channel) * 5 + 127) / 255;
+        (16 + 36 * level(self.r) + 6 * level(self.g) + level(self.b)) as u8
+    }
+
+    /// The closest of the 16 basic colours, as an index from 0 (black) to 7
+    /// (white) and whether it is the bright variant.
+    fn ansi16(self) -> (u8, bool) {
+        let channels = [self.r, self.g, self.b];
+        let max = channels.into_iter().max().unwrap();
+        let index = channels
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, channel)| channel > 0 && channel >= max / 2)
+            .map(|(bit, _)| 1 << bit)
+            .sum();
+        (index, max > 192)
+    }
 }
 
 impl Cell {
-    fn display(&self, width: usize) -> String {
+    fn width(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn display(&self, width: usize, style: Style) -> String {
         let mut text = format!("{:^width$}", self.text);
-        if let Some(Colour { r, g, b }) = self.fg {
-            text = format!("\x1b[38;2;{r};{g};{b}m{text}\x1b[0m");
+        if let Some(code) = self.fg.and_then(|fg| style.depth.sgr(fg, 30)) {
+            text = format!("\x1b[{code}m{text}\x1b[0m");
         };
-        if let Some(Colour { r, g, b }) = self.bg {
-            text = format!("\x1b[48;2;{r};{g};{b}m{text}\x1b[0m");
+        if let Some(code) = self.bg.and_then(|bg| style.depth.sgr(bg, 40)) {
+            text = format!("\x1b[{code}m{text}\x1b[0m");
         };
         text
     }
 
-    const fn fg(mut self, colour: Colour) -> Self {
+    /// Mark the cell as selected, with a background colour or brackets if
+    /// there is no colour.
+    pub fn highlight(self, style: Style) -> Self {
+        if style.depth == ColourDepth::None {
+            Self {
+                text: format!("[{}]", self.text),
+                ..self
+            }
+        } else {
+            self.bg(SELECTED)
+        }
+    }
+
+    pub const fn fg(mut self, colour: Colour) -> Self {
         self.fg = Some(colour);
         self
     }
 
-    const fn bg(mut self, colour: Colour) -> Self {
+    pub const fn bg(mut self, colour: Colour) -> Self {
         self.bg = Some(colour);
         self
     }
 }
 
-struct Row {
-    day: Cell,
-    parse: Cell,
-    part1: Cell,
-    part2: Cell,
-    total: Cell,
+/// A table whose columns are sized to fit their contents.
+pub struct Table {
+    header: Vec<Cell>,
+    body: Vec<Vec<Cell>>,
+    footer: Option<Vec<Cell>>,
 }
 
-impl Row {
-    fn print(&self) {
-        println!(
-            "│ {} │ {} │ {} │ {} │ {} │",
-            self.day.display(DAY_WIDTH),
-            self.parse.display(TIMING_WIDTH),
-            self.part1.display(TIMING_WIDTH),
-            self.part2.display(TIMING_WIDTH),
-            self.total.display(TIMING_WIDTH)
-        );
+impl Table {
+    pub fn new(columns: &[Column]) -> Self {
+        Self {
+            header: columns.iter().map(|column| column.title().into()).collect(),
+            body: Vec::new(),
+            footer: None,
+        }
     }
 
-    fn print_top() {
-        let day_sep = "─".repeat(DAY_WIDTH + 2);
-        let timing_sep = "─".repeat(TIMING_WIDTH + 2);
-        println!("╭{day_sep}┬{timing_sep}┬{timing_sep}┬{timing_sep}┬{timing_sep}╮");
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.body.push(row);
     }
 
-    fn print_bottom() {
-        let day_sep = "─".repeat(DAY_WIDTH + 2);
-        let timing_sep = "─".repeat(TIMING_WIDTH + 2);
-        println!("╰{day_sep}┴{timing_sep}┴{timing_sep}┴{timing_sep}┴{timing_sep}╯");
+    /// Set a row to be shown below the body, such as totals.
+    pub fn set_footer(&mut self, row: Vec<Cell>) {
+        self.footer = Some(row);
     }
 
-    fn print_sep() {
-        let day_sep = "─".repeat(DAY_WIDTH + 2);
-        let timing_sep = "─".repeat(TIMING_WIDTH + 2);
-        println!("├{day_sep}┼{timing_sep}┼{timing_sep}┼{timing_sep}┼{timing_sep}┤");
+    pub fn render(&self, style: Style) -> Vec<String> {
+        let mut widths: Vec<usize> = self.header.iter().map(Cell::width).collect();
+        for row in self.body.iter().chain(&self.footer) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width());
+            }
+        }
+        let render_row = |row: &[Cell]| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| cell.display(width, style))
+                .collect();
+            format!("│ {} │", cells.join(" │ "))
+        };
+        let border = |left: char, middle: char, right: char| {
+            let lines: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+            format!("{left}{}{right}", lines.join(&middle.to_string()))
+        };
+        let mut lines = vec![
+            border('╭', '┬', '╮'),
+            render_row(&self.header),
+            border('├', '┼', '┤'),
+        ];
+        lines.extend(self.body.iter().map(|row| render_row(row)));
+        if let Some(footer) = &self.footer {
+            lines.push(border('├', '┼', '┤'));
+            lines.push(render_row(footer));
+        }
+        lines.push(border('╰', '┴', '╯'));
+        lines
     }
 }
 
@@ -160,8 +483,28 @@ impl<'a> From<&'a str> for Cell {
     }
 }
 
+/// Where a timing falls between 1µs (0) and 100s (1).
+fn timing_position(timing: Duration) -> f64 {
+    ((timing.as_micros() as f64).log10() / 8.0).clamp(0.0, 1.0)
+}
+
+/// Interpolate between evenly spaced colour stops.
+fn gradient(stops: &[Colour], position: f64) -> Colour {
+    let scaled = position * (stops.len() - 1) as f64;
+    let index = (scaled as usize).min(stops.len() - 2);
+    let (from, to) = (stops[index], stops[index + 1]);
+    let mix = |a: u8, b: u8| {
+        (f64::from(b) - f64::from(a)).mul_add(scaled - index as f64, f64::from(a)) as u8
+    };
+    Colour {
+        r: mix(from.r, to.r),
+        g: mix(from.g, to.g),
+        b: mix(from.b, to.b),
+    }
+}
+
 fn timing_colour(timing: Duration) -> Colour {
-    let from = ((timing.as_micros() as f64).log10() / 8.0).clamp(0.0, 1.0);
+    let from = timing_position(timing);
     if from < 0.5 {
         Colour {
             r: (255.0 * from * 2.0) as u8,
@@ -176,3 +519,93 @@ fn timing_colour(timing: Duration) -> Colour {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn colour(r: u8, g: u8, b: u8) -> Colour {
+        Colour { r, g, b }
+    }
+
+    #[test]
+    fn test_ansi256() {
+        assert_eq!(colour(255, 0, 0).ansi256(), 196);
+        assert_eq!(colour(0, 0, 0).ansi256(), 16);
+        assert_eq!(colour(255, 255, 255).ansi256(), 231);
+        assert_eq!(colour(0, 128, 0).ansi256(), 34);
+    }
+
+    #[test]
+    fn test_ansi16() {
+        assert_eq!(colour(255, 0, 0).ansi16(), (1, true));
+        assert_eq!(colour(0, 0, 0).ansi16(), (0, false));
+        assert_eq!(colour(255, 255, 255).ansi16(), (7, true));
+        assert_eq!(colour(0, 128, 0).ansi16(), (2, false));
+        assert_eq!(colour(255, 200, 0).ansi16(), (3, true));
+    }
+
+    #[test]
+    fn test_no_colour() {
+        let cell = |style| format_timing(&Some(Ok(Duration::from_millis(200))), style);
+        let style = Style::detect(Some(false), Palette::Traffic, false);
+        assert_eq!(style.depth, ColourDepth::None);
+        assert!(style.symbols);
+        assert_eq!(cell(style).display(9, style), " 200ms ! ");
+
+        // Nothing else reads NO_COLOR, so setting it can't affect other tests.
+        std::env::set_var("NO_COLOR", "1");
+        let style = Style::detect(None, Palette::Traffic, false);
+        std::env::remove_var("NO_COLOR");
+        assert_eq!(style.depth, ColourDepth::None);
+        assert_eq!(cell(style).display(9, style), " 200ms ! ");
+
+        let style = Style {
+            depth: ColourDepth::TrueColour,
+            ..style
+        };
+        assert_eq!(
+            cell(style).display(5, style),
+            "\x1b[38;2;255;172;0m200ms !\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let style = Style::detect(Some(false), Palette::Traffic, false);
+        let ms = Duration::from_millis;
+        let mut table = Table::new(&[Column::Day, Column::Total, Column::Share, Column::Speedup]);
+        // Day 1 has a baseline three times slower, and day 25 has none.
+        table.push(vec![
+            "1".into(),
+            "1ms".into(),
+            format_share(ms(1), ms(4)),
+            format_speedup(Some(ms(3)), ms(1)),
+        ]);
+        table.push(vec![
+            "25".into(),
+            "3ms".into(),
+            format_share(ms(3), ms(4)),
+            format_speedup(None, ms(3)),
+        ]);
+        table.set_footer(vec![
+            "Total".into(),
+            "4ms".into(),
+            format_share(ms(4), ms(4)),
+            format_speedup(Some(ms(3)), ms(1)),
+        ]);
+        assert_eq!(
+            table.render(style),
+            [
+                "╭───────┬───────┬────────┬─────────╮",
+                "│  Day  │ Total │ Share  │ Speedup │",
+                "├───────┼───────┼────────┼─────────┤",
+                "│   1   │  1ms  │ 25.0%  │  3.00×  │",
+                "│  25   │  3ms  │ 75.0%  │    -    │",
+                "├───────┼───────┼────────┼─────────┤",
+                "│ Total │  4ms  │ 100.0% │  3.00×  │",
+                "╰───────┴───────┴────────┴─────────╯",
+            ]
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use rustc_hash::FxHashSet;
+
+use crate::vec2::Vec2;
+
+/// A set of 2D points.
+///
+/// [`DenseSet`] is a bitset over a fixed box, so is fastest when points are
+/// packed closely together in a known area. [`SparseSet`] is a hash set, so
+/// works for any points and uses less memory when they are spread out.
+pub trait PointSet: Clone {
+    type Iter<'a>: Iterator<Item = Vec2>
+    where
+        Self: 'a;
+
+    /// An empty set which can hold points between `min` and `max` (inclusive).
+    fn new(min: Vec2, max: Vec2) -> Self;
+    fn contains(&self, point: Vec2) -> bool;
+    /// Add a point, returning whether it was not already present.
+    fn insert(&mut self, point: Vec2) -> bool;
+    /// Remove a point, returning whether it was present.
+    fn remove(&mut self, point: Vec2) -> bool;
+    fn len(&self) -> usize;
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add every point in `other`.
+    #[allow(dead_code)]
+    fn union_with(&mut self, other: &Self) {
+        for point in other.iter() {
+            self.insert(point);
+        }
+    }
+
+    /// Remove every point not in `other`.
+    #[allow(dead_code)]
+    fn intersect_with(&mut self, other: &Self) {
+        let removed: Vec<_> = self
+            .iter()
+            .filter(|&point| !other.contains(point))
+            .collect();
+        for point in removed {
+            self.remove(point);
+        }
+    }
+
+    /// Remove every point in `other`.
+    #[allow(dead_code)]
+    fn difference_with(&mut self, other: &Self) {
+        for point in other.iter() {
+            self.remove(point);
+        }
+    }
+
+    /// Keep only the points in exactly one of the sets.
+    #[allow(dead_code)]
+    fn symmetric_difference_with(&mut self, other: &Self) {
+        for point in other.iter() {
+            if !self.remove(point) {
+                self.insert(point);
+            }
+        }
+    }
+}
+
+/// A bitset of the points in a box.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DenseSet {
+    min: Vec2,
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl DenseSet {
+    fn offset(&self, point: Vec2) -> Option<usize> {
+        let relative = point - self.min;
+        let (x, y) = (relative.x(), relative.y());
+        ((0..self.width as isize).contains(&x) && (0..self.height as isize).contains(&y))
+            .then(|| y as usize * self.width + x as usize)
+    }
+
+    fn point(&self, offset: usize) -> Vec2 {
+        self.min
+            + Vec2::new(
+                (offset % self.width) as isize,
+                (offset / self.width) as isize,
+            )
+    }
+
+    /// Combine the words of two sets with the same bounds.
+    fn combine(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) {
+        assert!(
+            self.min == other.min && self.width == other.width && self.height == other.height,
+            "dense sets have different bounds"
+        );
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word = op(*word, *other);
+        }
+        self.len = self
+            .words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum();
+    }
+}
+
+impl PointSet for DenseSet {
+    type Iter<'a> = DenseIter<'a>;
+
+    fn new(min: Vec2, max: Vec2) -> Self {
+        let width = (max.x() - min.x() + 1).max(0) as usize;
+        let height = (max.y() - min.y() + 1).max(0) as usize;
+        Self {
+            min,
+            width,
+            height,
+            words: vec![0; (width * height).div_ceil(64)],
+            len: 0,
+        }
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.offset(point)
+            .is_some_and(|offset| self.words[offset / 64] & (1 << (offset % 64)) != 0)
+    }
+
+    /// # Panics
+    ///
+    /// If the point is outside the set's bounds.
+    fn insert(&mut self, point: Vec2) -> bool {
+        let offset = self
+            .offset(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside the set's bounds"));
+        let word = &mut self.words[offset / 64];
+        let mask = 1 << (offset % 64);
+        let added = *word & mask == 0;
+        *word |= mask;
+        self.len += usize::from(added);
+        added
+    }
+
+    fn remove(&mut self, point: Vec2) -> bool {
+        let Some(offset) = self.offset(point) else {
+            return false;
+        };
+        let word = &mut self.words[offset / 64];
+        let mask = 1 << (offset % 64);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        self.len -= usize::from(removed);
+        removed
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> DenseIter<'_> {
+        DenseIter {
+            set: self,
+            word_index: 0,
+            word: self.words.first().copied().unwrap_or(0),
+        }
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a | b);
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & b);
+    }
+
+    fn difference_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a & !b);
+    }
+
+    fn symmetric_difference_with(&mut self, other: &Self) {
+        self.combine(other, |a, b| a ^ b);
+    }
+}
+
+pub struct DenseIter<'a> {
+    set: &'a DenseSet,
+    word_index: usize,
+    /// The bits of the current word which haven't been yielded yet.
+    word: u64,
+}
+
+impl Iterator for DenseIter<'_> {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Vec2> {
+        while self.word == 0 {
+            self.word_index += 1;
+            self.word = *self.set.words.get(self.word_index)?;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.set.point(self.word_index * 64 + bit))
+    }
+}
+
+/// A hash set of points, with no bounds.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SparseSet(FxHashSet<Vec2>);
+
+impl PointSet for SparseSet {
+    type Iter<'a> = std::iter::Copied<std::collections::hash_set::Iter<'a, Vec2>>;
+
+    fn new(_min: Vec2, _max: Vec2) -> Self {
+        Self::default()
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        self.0.contains(&point)
+    }
+
+    fn insert(&mut self, point: Vec2) -> bool {
+        self.0.insert(point)
+    }
+
+    fn remove(&mut self, point: Vec2) -> bool {
+        self.0.remove(&point)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.0.iter().copied()
+    }
+
+    fn intersect_with(&mut self, other: &Self) {
+        self.0.retain(|point| other.0.contains(point));
+    }
+
+    fn difference_with(&mut self, other: &Self) {
+        self.0.retain(|point| !other.0.contains(point));
+    }
+}
+
+impl FromIterator<Vec2> for SparseSet {
+    fn from_iter<I: IntoIterator<Item = Vec2>>(points: I) -> Self {
+        Self(points.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_algebra<S: PointSet>() {
+        let set = |points: &[(isize, isize)]| {
+            let mut set = S::new(Vec2::new(-5, -5), Vec2::new(100, 5));
+            for &(x, y) in points {
+                set.insert(Vec2::new(x, y));
+            }
+            set
+        };
+        let sorted = |set: &S| {
+            let mut points: Vec<_> = set.iter().map(|point| point.coords()).collect();
+            points.sort_unstable();
+            points
+        };
+        let a = set(&[(-5, -5), (0, 0), (70, 1)]);
+        let b = set(&[(0, 0), (100, 5)]);
+        let mut union = a.clone();
+        union.union_with(&b);
+        assert_eq!(sorted(&union), [[-5, -5], [0, 0], [70, 1], [100, 5]]);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b);
+        assert_eq!(sorted(&intersection), [[0, 0]]);
+        let mut difference = a.clone();
+        difference.difference_with(&b);
+        assert_eq!(sorted(&difference), [[-5, -5], [70, 1]]);
+        let mut symmetric = a;
+        symmetric.symmetric_difference_with(&b);
+        assert_eq!(symmetric.len(), 3);
+        assert!(!symmetric.contains(Vec2::new(0, 0)));
+        assert!(!symmetric.remove(Vec2::new(1000, 1000)));
+    }
+
+    #[test]
+    fn test_dense() {
+        check_algebra::<DenseSet>();
+    }
+
+    #[test]
+    fn test_sparse() {
+        check_algebra::<SparseSet>();
+    }
+
+    /// Fill a square region point by point, as in a flood fill.
+    fn fill_square<S: PointSet>(size: isize) -> usize {
+        let mut set = S::new(Vec2::ZERO, Vec2::new(size - 1, size - 1));
+        let mut open = vec![Vec2::ZERO];
+        while let Some(point) = open.pop() {
+            if point.within(Vec2::ZERO, Vec2::new(size - 1, size - 1)) && set.insert(point) {
+                open.extend(point.adjacents());
+            }
+        }
+        set.len()
+    }
+
+    /// Insert a few points spread over a large area.
+    fn scatter<S: PointSet>(spread: isize) -> usize {
+        let mut set = S::new(Vec2::ZERO, Vec2::new(spread, spread));
+        for i in 0..1000 {
+            set.insert(Vec2::new(i * 7919 % spread, i * 104_729 % spread));
+        }
+        set.iter().count()
+    }
+
+    #[bench]
+    fn bench_dense_fill(b: &mut test::Bencher) {
+        b.iter(|| fill_square::<DenseSet>(test::black_box(100)));
+    }
+
+    #[bench]
+    fn bench_sparse_fill(b: &mut test::Bencher) {
+        b.iter(|| fill_square::<SparseSet>(test::black_box(100)));
+    }
+
+    #[bench]
+    fn bench_dense_scatter(b: &mut test::Bencher) {
+        b.iter(|| scatter::<DenseSet>(test::black_box(10_000)));
+    }
+
+    #[bench]
+    fn bench_sparse_scatter(b: &mut test::Bencher) {
+        b.iter(|| scatter::<SparseSet>(test::black_box(10_000)));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{
+    output::Palette,
+    runner::{DayTimings, WrongAnswer},
+};
+use std::{fmt::Write, path::Path, time::Duration};
+
+const CHART_WIDTH: usize = 800;
+const LABEL_WIDTH: usize = 60;
+const HEADER_HEIGHT: usize = 50;
+const FOOTER_HEIGHT: usize = 30;
+const BAR_HEIGHT: usize = 7;
+const ROW_GAP: usize = 8;
+const ROW_HEIGHT: usize = BAR_HEIGHT * 3 + ROW_GAP;
+/// The chart's axis runs from 1ns to 10^`MAX_POWER` ns (10 seconds).
+const MAX_POWER: u32 = 10;
+
+/// Write `timings.svg`, a bar chart of the timings, and `timings.html`, a page
+/// with the timing table and the chart, to the given directory.
+pub fn write(timings: &[DayTimings], dir: &Path, palette: Palette) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let chart = svg_chart(timings, palette);
+    std::fs::write(dir.join("timings.svg"), &chart)?;
+    std::fs::write(
+        dir.join("timings.html"),
+        html_page(timings, &chart, palette),
+    )
+}
+
+/// The x coordinate of a timing on the log-scale axis.
+fn timing_x(timing: Duration) -> f64 {
+    let plot_width = (CHART_WIDTH - LABEL_WIDTH * 2) as f64;
+    let scale = (timing.as_nanos() as f64).max(1.0).log10() / f64::from(MAX_POWER);
+    scale
+        .clamp(0.0, 1.0)
+        .mul_add(plot_width, LABEL_WIDTH as f64)
+}
+
+const fn format_power(power: u32) -> &'static str {
+    [
+        "1ns", "10ns", "100ns", "1µs", "10µs", "100µs", "1ms", "10ms", "100ms", "1s", "10s",
+    ][power as usize]
+}
+
+fn svg_chart(timings: &[DayTimings], palette: Palette) -> String {
+    let height = HEADER_HEIGHT + ROW_HEIGHT * timings.len() + FOOTER_HEIGHT;
+    let plot_bottom = HEADER_HEIGHT + ROW_HEIGHT * timings.len();
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{CHART_WIDTH}" height="{height}" viewBox="0 0 {CHART_WIDTH} {height}" font-family="sans-serif" font-size="12">"#
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#1e1e1e"/>"##
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r##"<text x="{}" y="20" fill="#ffffff" font-size="16" text-anchor="middle">Advent of Code 2022 timings</text>"##,
+        CHART_WIDTH / 2
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r##"<text x="{}" y="38" fill="#aaaaaa" text-anchor="middle">Bars for each day show parsing, part 1 and part 2, top to bottom</text>"##,
+        CHART_WIDTH / 2
+    )
+    .unwrap();
+    for power in 0..=MAX_POWER {
+        let x = timing_x(Duration::from_nanos(10u64.pow(power)));
+        writeln!(
+            svg,
+            r##"<line x1="{x:.1}" y1="{HEADER_HEIGHT}" x2="{x:.1}" y2="{plot_bottom}" stroke="#444444"/>"##
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            r##"<text x="{x:.1}" y="{}" fill="#aaaaaa" text-anchor="middle">{}</text>"##,
+            plot_bottom + 18,
+            format_power(power)
+        )
+        .unwrap();
+    }
+    for (row, timing) in timings.iter().enumerate() {
+        let top = HEADER_HEIGHT + ROW_HEIGHT * row + ROW_GAP / 2;
+        writeln!(
+            svg,
+            r##"<text x="{}" y="{}" fill="#ffffff" text-anchor="end">Day {}</text>"##,
+            LABEL_WIDTH - 8,
+            top + BAR_HEIGHT * 2,
+            timing.day
+        )
+        .unwrap();
+        let parts = [
+            ("parse", timing.parse.map(Ok)),
+            ("part 1", timing.part1.clone()),
+            ("part 2", timing.part2.clone()),
+        ];
+        for (bar, (name, part)) in parts.into_iter().enumerate() {
+            let y = top + BAR_HEIGHT * bar;
+            let (width, colour, label) = match part {
+                None => continue,
+                Some(Ok(timing)) => (
+                    timing_x(timing) - LABEL_WIDTH as f64,
+                    palette.timing(timing),
+                    format!("{timing:?}"),
+                ),
+                Some(Err(WrongAnswer { expected, actual })) => (
+                    (CHART_WIDTH - LABEL_WIDTH * 2) as f64,
+                    palette.wrong(),
+                    escape_html(&format!("wrong answer: {actual} (expected {expected})")),
+                ),
+            };
+            writeln!(
+                svg,
+                r#"<rect x="{LABEL_WIDTH}" y="{y}" width="{width:.1}" height="{}" fill="{}"><title>Day {} {name}: {label}</title></rect>"#,
+                BAR_HEIGHT - 1,
+                colour.hex(),
+                timing.day
+            )
+            .unwrap();
+        }
+        let total = timing.total();
+        writeln!(
+            svg,
+            r##"<text x="{}" y="{}" fill="#aaaaaa">{total:.1?}</text>"##,
+            CHART_WIDTH - LABEL_WIDTH + 4,
+            top + BAR_HEIGHT * 2
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escape text to go in HTML or SVG, including inside attributes.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn html_cell(timing: Option<&Result<Duration, WrongAnswer>>, palette: Palette) -> String {
+    match timing {
+        Some(Ok(timing)) => format!(
+            r#"<td style="color: {}">{timing:?}</td>"#,
+            palette.timing(*timing).hex()
+        ),
+        Some(Err(WrongAnswer { expected, actual })) => format!(
+            r#"<td class="wrong" style="background: {}" title="{}">Wrong!</td>"#,
+            palette.wrong().hex(),
+            escape_html(&format!("gave {actual} (expected {expected})"))
+        ),
+        None => "<td>-</td>".to_string(),
+    }
+}
+
+fn html_page(timings: &[DayTimings], chart: &str, palette: Palette) -> String {
+    let mut rows = String::new();
+    let mut totals = [Duration::ZERO; 3];
+    for timing in timings {
+        let durations = timing.durations();
+        for (total, duration) in totals.iter_mut().zip(durations) {
+            *total += duration;
+        }
+        writeln!(
+            rows,
+            "<tr><td>{}</td>{}{}{}<td>{:?}</td></tr>",
+            timing.day,
+            html_cell(timing.parse.map(Ok).as_ref(), palette),
+            html_cell(timing.part1.as_ref(), palette),
+            html_cell(timing.part2.as_ref(), palette),
+            timing.total()
+        )
+        .unwrap();
+    }
+    let [parse, part1, part2] = totals;
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Advent of Code 2022 timings</title>
+<style>
+body {{ background: #1e1e1e; color: #ffffff; font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; margin-bottom: 2em; }}
+th, td {{ border: 1px solid #444444; padding: 0.3em 1em; text-align: center; font-family: monospace; }}
+tfoot td {{ font-weight: bold; }}
+td.wrong {{ color: #000000; }}
+</style>
+</head>
+<body>
+<h1>Advent of Code 2022 timings</h1>
+<table>
+<thead><tr><th>Day</th><th>Parse</th><th>Part 1</th><th>Part 2</th><th>Total</th></tr></thead>
+<tbody>
+{rows}</tbody>
+<tfoot><tr><td>Total</td><td>{parse:?}</td><td>{part1:?}</td><td>{part2:?}</td><td>{:?}</td></tr></tfoot>
+</table>
+{chart}</body>
+</html>
+"#,
+        parse + part1 + part2
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrong_answers_are_escaped() {
+        let wrong = Err(WrongAnswer {
+            expected: "a<b".to_string(),
+            actual: "\"x\" & y".to_string(),
+        });
+        assert_eq!(
+            html_cell(Some(&wrong), Palette::Traffic),
+            format!(
+                r#"<td class="wrong" style="background: {}" title="gave &quot;x&quot; &amp; y (expected a&lt;b)">Wrong!</td>"#,
+                Palette::Traffic.wrong().hex()
+            )
+        );
+    }
+}
@@ -1,26 +1,101 @@
 use crate::{cli::DayPick, data, timer, Day};
 use log::{error, info};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-pub fn run(data: data::All, picks: &[DayPick; 25]) -> Vec<DayTimings> {
-    Runner { data, picks }.run()
+pub fn run(data: data::All, picks: &[DayPick; 25], variants: &[String]) -> Vec<DayTimings> {
+    Runner::new(data, &log_event)
+        .with_variants(variants)
+        .run(picks)
 }
 
+/// The alternative solutions which can be chosen with `--variant`, and the
+/// day each one solves. Each day has at most one.
+pub const VARIANTS: [(&str, usize); 2] = [("dense", 17), ("bitwise", 23)];
+
 macro_rules! run_days {
-    ( $runner:expr, { $( $num:literal => $day_mod:ident::$day_type:ident, )* } ) => {
-        [$( $runner.run_day($num, crate::days::$day_mod::$day_type::parse) ),*]
+    (
+        $runner:expr,
+        $pick:expr,
+        {
+            $(
+                $num:literal => $day_mod:ident::$day_type:ident
+                    $( { $( $variant:literal => $variant_type:ident ),* } )?,
+            )*
+        }
+    ) => {
+        match ($pick.day, $runner.variant($pick.day)) {
+            $(
+                $( $(
+                    ($num, Some($variant)) => {
+                        $runner.run_day($pick, crate::days::$day_mod::$variant_type::parse)
+                    }
+                )* )?
+                ($num, _) => $runner.run_day($pick, crate::days::$day_mod::$day_type::parse),
+            )*
+            _ => None,
+        }
     };
 }
 
-struct Runner<'a> {
+/// Which stage of a day a progress [`Event`] refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    Parse,
+    Part1,
+    Part2,
+}
+
+/// Emitted by the [`Runner`] as each part of a day starts and finishes.
+#[derive(Clone, Debug)]
+pub enum Event {
+    Started(usize, Part),
+    Finished(usize, Part, Result<Duration, WrongAnswer>),
+}
+
+pub struct Runner<'a> {
     data: data::All,
-    picks: &'a [DayPick; 25],
+    on_event: &'a dyn Fn(Event),
+    /// Which of the [`VARIANTS`] to run instead of the usual solutions, for
+    /// the days they solve.
+    variants: Vec<String>,
 }
 
 impl<'a> Runner<'a> {
-    fn run(&self) -> Vec<DayTimings> {
+    pub fn new(data: data::All, on_event: &'a dyn Fn(Event)) -> Self {
+        Self {
+            data,
+            on_event,
+            variants: Vec::new(),
+        }
+    }
+
+    pub fn with_variants(mut self, variants: &[String]) -> Self {
+        self.variants = variants.to_vec();
+        self
+    }
+
+    /// The variant chosen for a day, if any.
+    fn variant(&self, day: usize) -> Option<&str> {
+        VARIANTS
+            .into_iter()
+            .find(|&(name, variant_day)| {
+                variant_day == day && self.variants.iter().any(|chosen| chosen == name)
+            })
+            .map(|(name, _)| name)
+    }
+
+    pub fn run(&self, picks: &[DayPick; 25]) -> Vec<DayTimings> {
+        picks
+            .iter()
+            .filter_map(|&pick| self.run_pick(pick))
+            .collect()
+    }
+
+    pub fn run_pick(&self, pick: DayPick) -> Option<DayTimings> {
         run_days!(
             self,
+            pick,
             {
                 1 => day01::Day1,
                 2 => day02::Day2,
@@ -38,72 +113,105 @@ impl<'a> Runner<'a> {
                 14 => day14::Day14,
                 15 => day15::Day15,
                 16 => day16::Day16,
-                17 => day17::Day17,
+                17 => day17::Day17 { "dense" => Day17Dense },
                 18 => day18::Day18,
                 19 => day19::Day19,
                 20 => day20::Day20,
                 21 => day21::Day21,
                 22 => day22::Day22,
-                23 => day23::Day23,
+                23 => day23::Day23 { "bitwise" => Day23Bitwise },
                 24 => day24::Day24,
                 25 => day25::Day25,
             }
         )
-        .into_iter()
-        .flatten()
-        .collect()
     }
 
     fn run_day<D: Day>(
         &self,
-        day_num: usize,
+        picks: DayPick,
         parse_fn: fn(&'static str) -> D,
     ) -> Option<DayTimings> {
+        let day_num = picks.day;
         let data = self.data[day_num - 1];
-        let picks = self.picks[day_num - 1];
         if !(picks.parse || picks.part1 || picks.part2) {
             return None;
         }
         let (parse, day) = if picks.parse {
+            (self.on_event)(Event::Started(day_num, Part::Parse));
             let (timing, day) = timer::time(|| Ok::<_, ()>(parse_fn(data.input))).unwrap();
-            report_parse(day_num, timing);
+            (self.on_event)(Event::Finished(day_num, Part::Parse, Ok(timing)));
             (Some(timing), day)
         } else {
             (None, parse_fn(data.input))
         };
         let part1 = if picks.part1 {
+            (self.on_event)(Event::Started(day_num, Part::Part1));
             let result = timer::time(|| answer(day.part1(), data.part1)).map(|(d, ())| d);
-            report_part(&format!("Day {day_num} part 1"), &result);
+            (self.on_event)(Event::Finished(day_num, Part::Part1, result.clone()));
             Some(result)
         } else {
             None
         };
         let part2 = if picks.part2 {
+            (self.on_event)(Event::Started(day_num, Part::Part2));
             let result = timer::time(|| answer(day.part2(), data.part2)).map(|(d, ())| d);
-            report_part(&format!("Day {day_num} part 2"), &result);
+            (self.on_event)(Event::Finished(day_num, Part::Part2, result.clone()));
             Some(result)
         } else {
             None
         };
+        let answers = [
+            part1
+                .as_ref()
+                .map(|result| given_answer(result, data.part1)),
+            part2
+                .as_ref()
+                .map(|result| given_answer(result, data.part2)),
+        ];
         Some(DayTimings {
             day: day_num as u32,
             parse,
             part1,
             part2,
+            answers,
         })
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct DayTimings {
     pub day: u32,
     pub parse: Option<Duration>,
     pub part1: Option<Result<Duration, WrongAnswer>>,
     pub part2: Option<Result<Duration, WrongAnswer>>,
+    /// The answers given by each part which was run.
+    #[serde(default)]
+    pub answers: [Option<String>; 2],
+}
+
+impl DayTimings {
+    /// Timings for parsing and each part, counting parts which were skipped or
+    /// gave the wrong answer as zero.
+    pub fn durations(&self) -> [Duration; 3] {
+        let correct = |part: &Option<Result<Duration, WrongAnswer>>| match part {
+            Some(Ok(timing)) => *timing,
+            _ => Duration::ZERO,
+        };
+        [
+            self.parse.unwrap_or(Duration::ZERO),
+            correct(&self.part1),
+            correct(&self.part2),
+        ]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.durations().iter().sum()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WrongAnswer {
-    pub expected: &'static str,
+    pub expected: String,
     pub actual: String,
 }
 
@@ -111,12 +219,37 @@ fn answer(actual: String, expected: &'static str) -> Result<(), WrongAnswer> {
     if actual == expected {
         Ok(())
     } else {
-        Err(WrongAnswer { expected, actual })
+        Err(WrongAnswer {
+            expected: expected.to_string(),
+            actual,
+        })
     }
 }
 
-fn report_parse(day: usize, timing: Duration) {
-    info!("Day {} parsed in {:?}", day, timing);
+/// The answer a part gave, given whether it matched the expected answer.
+pub fn given_answer(result: &Result<Duration, WrongAnswer>, expected: &str) -> String {
+    match result {
+        Ok(_) => expected.to_string(),
+        Err(WrongAnswer { actual, .. }) => actual.clone(),
+    }
+}
+
+/// Report progress through the log, as used when printing the table at the end.
+pub fn log_event(event: Event) {
+    match event {
+        Event::Started(..) => {}
+        Event::Finished(day, Part::Parse, Ok(timing)) => {
+            info!("Day {day} parsed in {timing:?}");
+        }
+        Event::Finished(day, part, result) => {
+            let part_name = match part {
+                Part::Parse => format!("Day {day} parse"),
+                Part::Part1 => format!("Day {day} part 1"),
+                Part::Part2 => format!("Day {day} part 2"),
+            };
+            report_part(&part_name, &result);
+        }
+    }
 }
 
 fn report_part(part_name: &str, result: &Result<Duration, WrongAnswer>) {
@@ -0,0 +1,192 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use rustc_hash::FxHashSet;
+
+/// A graph to search for a path from a start state to any goal state.
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+
+    fn start(&self) -> Self::State;
+    fn is_goal(&self, state: &Self::State) -> bool;
+    /// The states reachable from a state in one step, with the cost of each step.
+    fn successors(&self, state: &Self::State) -> impl Iterator<Item = (Self::State, usize)>;
+
+    /// If every state has an index below some count, that count. Searches
+    /// then mark states as seen in a bitset rather than hashing them.
+    fn state_count(&self) -> Option<usize> {
+        None
+    }
+
+    /// The index of a state, below [`Self::state_count`]. Only called if
+    /// that returns a count.
+    fn index(&self, _state: &Self::State) -> usize {
+        unimplemented!("states have no dense index")
+    }
+}
+
+/// A path found by a search, from the start state to a goal state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S> {
+    pub states: Vec<S>,
+    pub cost: usize,
+}
+
+impl<S> Path<S> {
+    /// The number of steps taken.
+    pub const fn len(&self) -> usize {
+        self.states.len() - 1
+    }
+}
+
+/// A state reached during a search, and how it was reached.
+struct Node<S> {
+    state: S,
+    cost: usize,
+    parent: Option<usize>,
+}
+
+/// Follow parents back from a node to build the path to it.
+fn trace_path<S: Clone>(nodes: &[Node<S>], mut index: usize) -> Path<S> {
+    let cost = nodes[index].cost;
+    let mut states = vec![nodes[index].state.clone()];
+    while let Some(parent) = nodes[index].parent {
+        states.push(nodes[parent].state.clone());
+        index = parent;
+    }
+    states.reverse();
+    Path { states, cost }
+}
+
+/// The states a search has already reached.
+enum Seen<'a, P: SearchProblem> {
+    Dense(&'a P, Vec<u64>),
+    Hashed(FxHashSet<P::State>),
+}
+
+impl<'a, P: SearchProblem> Seen<'a, P> {
+    fn new(problem: &'a P) -> Self {
+        problem.state_count().map_or_else(
+            || Self::Hashed(FxHashSet::default()),
+            |count| Self::Dense(problem, vec![0; count.div_ceil(64)]),
+        )
+    }
+
+    /// Mark a state as seen, returning whether it wasn't already.
+    fn insert(&mut self, state: &P::State) -> bool {
+        match self {
+            Self::Dense(problem, bits) => {
+                let index = problem.index(state);
+                let mask = 1 << (index % 64);
+                let unseen = bits[index / 64] & mask == 0;
+                bits[index / 64] |= mask;
+                unseen
+            }
+            Self::Hashed(states) => states.insert(state.clone()),
+        }
+    }
+}
+
+/// Find the path with the fewest steps, ignoring step costs (although the
+/// path's cost is still the total of its steps).
+pub fn bfs<P: SearchProblem>(problem: &P) -> Option<Path<P::State>> {
+    let start = problem.start();
+    let mut seen = Seen::new(problem);
+    seen.insert(&start);
+    let mut nodes = vec![Node {
+        state: start,
+        cost: 0,
+        parent: None,
+    }];
+    let mut open = VecDeque::from([0]);
+    while let Some(index) = open.pop_front() {
+        let Node { state, cost, .. } = &nodes[index];
+        if problem.is_goal(state) {
+            return Some(trace_path(&nodes, index));
+        }
+        let (state, cost) = (state.clone(), *cost);
+        for (next, step) in problem.successors(&state) {
+            if seen.insert(&next) {
+                nodes.push(Node {
+                    state: next,
+                    cost: cost + step,
+                    parent: Some(index),
+                });
+                open.push_back(nodes.len() - 1);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::Grid, vec2::Vec2};
+
+    /// Walk from `S` to `E` around walls (`#`). Stepping onto a digit costs
+    /// that much, and anything else costs 1.
+    struct Maze {
+        grid: Grid<char>,
+        dense: bool,
+    }
+
+    impl Maze {
+        fn new(input: &str, dense: bool) -> Self {
+            Self {
+                grid: Grid::parse(input, |c| c),
+                dense,
+            }
+        }
+    }
+
+    impl SearchProblem for Maze {
+        type State = Vec2;
+
+        fn start(&self) -> Vec2 {
+            self.grid.find(|&c| c == 'S').unwrap()
+        }
+
+        fn is_goal(&self, state: &Vec2) -> bool {
+            self.grid[*state] == 'E'
+        }
+
+        fn successors(&self, state: &Vec2) -> impl Iterator<Item = (Vec2, usize)> {
+            self.grid
+                .cardinal_neighbours(*state)
+                .filter(|&next| self.grid[next] != '#')
+                .map(|next| (next, self.grid[next].to_digit(10).unwrap_or(1) as usize))
+        }
+
+        fn state_count(&self) -> Option<usize> {
+            self.dense.then(|| self.grid.width() * self.grid.height())
+        }
+
+        fn index(&self, state: &Vec2) -> usize {
+            self.grid.offset(*state).unwrap()
+        }
+    }
+
+    const MAZE: &str = "\
+S.9.#
+.#9.#
+.#...
+...#E
+";
+
+    #[test]
+    fn test_bfs() {
+        for dense in [false, true] {
+            let shortest = bfs(&Maze::new(MAZE, dense)).unwrap();
+            assert_eq!((shortest.len(), shortest.cost), (7, 15));
+            assert_eq!(shortest.states[0], Vec2::new(0, 0));
+            assert_eq!(shortest.states[7], Vec2::new(4, 3));
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        for dense in [false, true] {
+            assert!(bfs(&Maze::new("S#E\n", dense)).is_none());
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    cli::DayPick,
+    data,
+    output::{format_timing, Cell, Column, Style, Table},
+    runner::{given_answer, DayTimings, Event, Part, Runner, WrongAnswer},
+};
+use crossterm::{
+    cursor,
+    event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::Print,
+    terminal,
+};
+use std::{
+    io::Write,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const FRAME_TIME: Duration = Duration::from_millis(100);
+
+/// Run the picked days while showing a live table of their timings, until the
+/// user quits. Returns the timings which had been measured by then.
+pub fn run(
+    data: data::All,
+    picks: &[DayPick; 25],
+    style: Style,
+    variants: Vec<String>,
+) -> Vec<DayTimings> {
+    let (job_sender, jobs) = mpsc::channel::<DayPick>();
+    let (event_sender, events) = mpsc::channel();
+    let expected: Vec<[&str; 2]> = data.iter().map(|day| [day.part1, day.part2]).collect();
+    std::thread::spawn(move || {
+        let on_event = |event| {
+            // The dashboard hanging up just means we are about to exit.
+            let _ = event_sender.send(event);
+        };
+        let runner = Runner::new(data, &on_event).with_variants(&variants);
+        for pick in jobs {
+            runner.run_pick(pick);
+        }
+    });
+    let mut dashboard = Dashboard::new(picks, style);
+    for &pick in picks {
+        if dashboard.queue(pick) {
+            job_sender.send(pick).unwrap();
+        }
+    }
+    {
+        let _terminal = RawTerminal::enter();
+        while let Some(pick) = dashboard.tick(&events) {
+            if dashboard.queue(pick) {
+                job_sender.send(pick).unwrap();
+            }
+        }
+    }
+    dashboard.timings(&expected)
+}
+
+/// Puts the terminal into raw mode on an alternate screen, and restores it
+/// when dropped (including on panic).
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> Self {
+        terminal::enable_raw_mode().unwrap();
+        execute!(
+            std::io::stdout(),
+            terminal::EnterAlternateScreen,
+            cursor::Hide
+        )
+        .unwrap();
+        Self
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(
+            std::io::stdout(),
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Status {
+    NotRun,
+    Queued,
+    Running(Instant),
+    Done(Result<Duration, WrongAnswer>),
+}
+
+impl Status {
+    fn cell(&self, style: Style) -> Cell {
+        match self {
+            Self::NotRun => Cell::from("-"),
+            Self::Queued => Cell::from("queued"),
+            Self::Running(start) => {
+                let elapsed = start.elapsed();
+                let frame = SPINNER
+                    [(elapsed.as_millis() / FRAME_TIME.as_millis()) as usize % SPINNER.len()];
+                Cell::from(format!("{frame} {elapsed:.1?}"))
+            }
+            Self::Done(result) => format_timing(&Some(result.clone()), style),
+        }
+    }
+
+    const fn timing(&self) -> Duration {
+        match self {
+            Self::Done(Ok(timing)) => *timing,
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+struct DayRow {
+    day: usize,
+    parts: [Status; 3],
+}
+
+/// Columns which can be selected: the day itself (to re-run all of it) and
+/// each of its parts.
+const COLUMNS: [Column; 4] = [Column::Day, Column::Parse, Column::Part1, Column::Part2];
+
+const fn part_index(part: Part) -> usize {
+    match part {
+        Part::Parse => 0,
+        Part::Part1 => 1,
+        Part::Part2 => 2,
+    }
+}
+
+struct Dashboard {
+    rows: Vec<DayRow>,
+    selected_row: usize,
+    selected_column: usize,
+    running: Option<(usize, Part)>,
+    style: Style,
+}
+
+impl Dashboard {
+    fn new(picks: &[DayPick; 25], style: Style) -> Self {
+        let rows = picks
+            .iter()
+            .filter(|pick| pick.parse || pick.part1 || pick.part2)
+            .map(|pick| DayRow {
+                day: pick.day,
+                parts: [Status::NotRun, Status::NotRun, Status::NotRun],
+            })
+            .collect();
+        Self {
+            rows,
+            selected_row: 0,
+            selected_column: 0,
+            running: None,
+            style,
+        }
+    }
+
+    /// Mark the parts of a pick as queued. Returns false if nothing was picked.
+    fn queue(&mut self, pick: DayPick) -> bool {
+        let Some(row) = self.rows.iter_mut().find(|row| row.day == pick.day) else {
+            return false;
+        };
+        let mut any = false;
+        for (picked, status) in [pick.parse, pick.part1, pick.part2]
+            .into_iter()
+            .zip(&mut row.parts)
+        {
+            if picked {
+                *status = Status::Queued;
+                any = true;
+            }
+        }
+        any
+    }
+
+    /// Apply progress updates, redraw and handle input. Returns a pick to
+    /// re-run if one was requested, or `None` if the user quit.
+    fn tick(&mut self, events: &mpsc::Receiver<Event>) -> Option<DayPick> {
+        loop {
+            for event in events.try_iter() {
+                self.apply(event);
+            }
+            self.draw();
+            if !event::poll(FRAME_TIME).unwrap() {
+                continue;
+            }
+            // Some terminals also report key releases, which mustn't count twice.
+            let event::Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read().unwrap()
+            else {
+                continue;
+            };
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => return None,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return None,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected_row = self.selected_row.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.selected_row =
+                        (self.selected_row + 1).min(self.rows.len().saturating_sub(1));
+                }
+                KeyCode::Left | KeyCode::Char('h') => {
+                    self.selected_column = self.selected_column.saturating_sub(1);
+                }
+                KeyCode::Right | KeyCode::Char('l') => {
+                    self.selected_column = (self.selected_column + 1).min(COLUMNS.len() - 1);
+                }
+                KeyCode::Enter | KeyCode::Char('r') if !self.rows.is_empty() => {
+                    return Some(self.selected_pick());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply(&mut self, event: Event) {
+        let (day, part, status) = match event {
+            Event::Started(day, part) => {
+                self.running = Some((day, part));
+                (day, part, Status::Running(Instant::now()))
+            }
+            Event::Finished(day, part, result) => {
+                self.running = None;
+                (day, part, Status::Done(result))
+            }
+        };
+        if let Some(row) = self.rows.iter_mut().find(|row| row.day == day) {
+            row.parts[part_index(part)] = status;
+        }
+    }
+
+    fn selected_pick(&self) -> DayPick {
+        let day = self.rows[self.selected_row].day;
+        let selected = COLUMNS[self.selected_column];
+        let only = |column| selected == Column::Day || selected == column;
+        DayPick {
+            day,
+            parse: only(Column::Parse),
+            part1: only(Column::Part1),
+            part2: only(Column::Part2),
+        }
+    }
+
+    fn draw(&self) {
+        let mut table = Table::new(&Column::DEFAULT);
+        let mut totals = [Duration::ZERO; 3];
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            for (total, status) in totals.iter_mut().zip(&row.parts) {
+                *total += status.timing();
+            }
+            let highlight = |column: Column, cell: Cell| {
+                if row_idx == self.selected_row && COLUMNS[self.selected_column] == column {
+                    cell.highlight(self.style)
+                } else {
+                    cell
+                }
+            };
+            let [parse, part1, part2] = &row.parts;
+            let total: Duration = row.parts.iter().map(Status::timing).sum();
+            table.push(vec![
+                highlight(Column::Day, Cell::from(row.day.to_string())),
+                highlight(Column::Parse, parse.cell(self.style)),
+                highlight(Column::Part1, part1.cell(self.style)),
+                highlight(Column::Part2, part2.cell(self.style)),
+                Cell::from(format!("{total:?}")),
+            ]);
+        }
+        let [parse, part1, part2] = totals;
+        table.set_footer(vec![
+            Cell::from("Total"),
+            Cell::from(format!("{parse:?}")),
+            Cell::from(format!("{part1:?}")),
+            Cell::from(format!("{part2:?}")),
+            Cell::from(format!("{:?}", parse + part1 + part2)),
+        ]);
+        let mut lines = table.render(self.style);
+        lines.push(match self.running {
+            Some((day, Part::Parse)) => format!("Parsing day {day}..."),
+            Some((day, Part::Part1)) => format!("Running day {day} part 1..."),
+            Some((day, Part::Part2)) => format!("Running day {day} part 2..."),
+            None => "Idle.".to_string(),
+        });
+        lines.push("←↑↓→ select · r/enter re-run · q quit".to_string());
+        let mut stdout = std::io::stdout();
+        queue!(
+            stdout,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(terminal::ClearType::All),
+            Print(lines.join("\r\n"))
+        )
+        .unwrap();
+        stdout.flush().unwrap();
+    }
+
+    /// The timings of every part which has finished. `expected` holds the
+    /// expected answers for each day.
+    fn timings(&self, expected: &[[&str; 2]]) -> Vec<DayTimings> {
+        self.rows
+            .iter()
+            .filter(|row| row.parts.iter().any(|part| matches!(part, Status::Done(_))))
+            .map(|row| {
+                let result = |status: &Status| match status {
+                    Status::Done(result) => Some(result.clone()),
+                    _ => None,
+                };
+                let part1 = result(&row.parts[1]);
+                let part2 = result(&row.parts[2]);
+                let [expected1, expected2] = expected[row.day - 1];
+                let answers = [
+                    part1.as_ref().map(|result| given_answer(result, expected1)),
+                    part2.as_ref().map(|result| given_answer(result, expected2)),
+                ];
+                DayTimings {
+                    day: row.day as u32,
+                    parse: result(&row.parts[0]).and_then(Result::ok),
+                    part1,
+                    part2,
+                    answers,
+                }
+            })
+            .collect()
+    }
+}
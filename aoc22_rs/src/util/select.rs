@@ -0,0 +1,171 @@
+//! Picking the largest or smallest few items without sorting them all.
+
+use std::{cmp::Ordering, cmp::Reverse, collections::BinaryHeap};
+
+/// The `k` smallest items in ascending order, keeping at most `k` of them at
+/// a time in a heap.
+fn smallest<E: Ord>(items: impl IntoIterator<Item = E>, k: usize) -> Vec<E> {
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    if k == 0 {
+        return Vec::new();
+    }
+    for item in items {
+        if heap.len() == k {
+            let mut largest = heap.peek_mut().unwrap();
+            if item < *largest {
+                *largest = item;
+            }
+        } else {
+            heap.push(item);
+        }
+    }
+    heap.into_sorted_vec()
+}
+
+/// An item ordered only by its key.
+struct Keyed<K, T> {
+    key: K,
+    item: T,
+}
+
+impl<K: Ord, T> PartialEq for Keyed<K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, T> Eq for Keyed<K, T> {}
+
+impl<K: Ord, T> PartialOrd for Keyed<K, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, T> Ord for Keyed<K, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// The `k` largest items, largest first. Returns fewer if there aren't
+/// enough items.
+pub fn max_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    smallest(items.into_iter().map(Reverse), k)
+        .into_iter()
+        .map(|Reverse(item)| item)
+        .collect()
+}
+
+/// The `k` smallest items, smallest first. Returns fewer if there aren't
+/// enough items.
+#[allow(dead_code)]
+pub fn min_k<T: Ord>(items: impl IntoIterator<Item = T>, k: usize) -> Vec<T> {
+    smallest(items, k)
+}
+
+/// The `k` items with the largest keys, largest first.
+#[allow(dead_code)]
+pub fn max_k_by_key<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    mut key: impl FnMut(&T) -> K,
+) -> Vec<T> {
+    let keyed = items.into_iter().map(|item| Keyed {
+        key: Reverse(key(&item)),
+        item,
+    });
+    smallest(keyed, k)
+        .into_iter()
+        .map(|keyed| keyed.item)
+        .collect()
+}
+
+/// The `k` items with the smallest keys, smallest first.
+#[allow(dead_code)]
+pub fn min_k_by_key<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    mut key: impl FnMut(&T) -> K,
+) -> Vec<T> {
+    let keyed = items.into_iter().map(|item| Keyed {
+        key: key(&item),
+        item,
+    });
+    smallest(keyed, k)
+        .into_iter()
+        .map(|keyed| keyed.item)
+        .collect()
+}
+
+/// The `N` largest items, largest first, or `None` if there are fewer than
+/// `N` items.
+pub fn try_max_n<const N: usize, T: Ord>(items: impl IntoIterator<Item = T>) -> Option<[T; N]> {
+    max_k(items, N).try_into().ok()
+}
+
+/// The `N` largest items, largest first.
+///
+/// # Panics
+///
+/// If there are fewer than `N` items.
+#[allow(dead_code)]
+pub fn max_n<const N: usize, T: Ord>(items: impl IntoIterator<Item = T>) -> [T; N] {
+    try_max_n(items).unwrap_or_else(|| panic!("fewer than {N} items"))
+}
+
+/// Reorder a slice so that its `k` smallest items come first, in order. The
+/// order of the rest is unspecified.
+#[allow(dead_code)]
+pub fn partial_sort<T: Ord>(items: &mut [T], k: usize) {
+    if k < items.len() {
+        items.select_nth_unstable(k);
+    }
+    let k = k.min(items.len());
+    items[..k].sort_unstable();
+}
+
+/// The item which would be at index `k` if the slice were sorted, reordering
+/// the slice to find it. Returns `None` if `k` is out of bounds.
+#[allow(dead_code)]
+pub fn kth_smallest<T: Ord>(items: &mut [T], k: usize) -> Option<&T> {
+    (k < items.len()).then(|| &*items.select_nth_unstable(k).1)
+}
+
+/// The item which would be at index `k` if the slice were sorted in
+/// descending order. Returns `None` if `k` is out of bounds.
+#[allow(dead_code)]
+pub fn kth_largest<T: Ord>(items: &mut [T], k: usize) -> Option<&T> {
+    let len = items.len();
+    (k < len).then(|| &*items.select_nth_unstable(len - 1 - k).1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_n() {
+        assert_eq!(max_n::<3, usize>(vec![1, 2, 3, 4, 5]), [5, 4, 3]);
+        assert_eq!(
+            max_n::<3, usize>(vec![2, 6, 5, 4, 10, 9, 1, 7, 3, 8]),
+            [10, 9, 8]
+        );
+    }
+
+    #[test]
+    fn test_selection() {
+        let items = [2, 6, 5, 4, 10, 9, 1, 7, 3, 8];
+        assert_eq!(try_max_n::<2, usize>(vec![1]), None);
+        assert_eq!(max_k(items, 20).len(), 10);
+        assert_eq!(min_k(items, 3), [1, 2, 3]);
+        assert_eq!(max_k_by_key(items, 2, |&x| x % 5), [4, 9]);
+        assert_eq!(min_k_by_key(items, 2, |&x| Reverse(x)), [10, 9]);
+        let mut sorted = items;
+        partial_sort(&mut sorted, 4);
+        assert_eq!(sorted[..4], [1, 2, 3, 4]);
+        assert_eq!(kth_smallest(&mut sorted, 0), Some(&1));
+        assert_eq!(kth_largest(&mut sorted, 1), Some(&9));
+        assert_eq!(kth_largest(&mut sorted, 10), None);
+    }
+}
@@ -0,0 +1,317 @@
+pub mod transform;
+
+use std::marker::PhantomData;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+};
+
+/// A `D` dimensional vector of integers. `T` is a tag for the coordinate space
+/// the vector belongs to, so vectors from different spaces can't be mixed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VecN<const D: usize, T = ()> {
+    coords: [isize; D],
+    space: PhantomData<T>,
+}
+
+pub type Vec2<T = ()> = VecN<2, T>;
+pub type Vec3<T = ()> = VecN<3, T>;
+
+pub const CARDINALS: [Vec2; 4] = [
+    Vec2::new(0, -1),
+    Vec2::new(1, 0),
+    Vec2::new(0, 1),
+    Vec2::new(-1, 0),
+];
+
+pub const DIAGONALS: [Vec2; 4] = [
+    Vec2::new(1, -1),
+    Vec2::new(1, 1),
+    Vec2::new(-1, 1),
+    Vec2::new(-1, -1),
+];
+
+impl<const D: usize, T> VecN<D, T> {
+    pub const ZERO: Self = Self::from_coords([0; D]);
+
+    pub const fn from_coords(coords: [isize; D]) -> Self {
+        Self {
+            coords,
+            space: PhantomData,
+        }
+    }
+
+    pub const fn coords(&self) -> [isize; D] {
+        self.coords
+    }
+
+    pub const fn x(&self) -> isize {
+        self.coords[0]
+    }
+
+    pub const fn y(&self) -> isize {
+        self.coords[1]
+    }
+
+    fn map(&self, f: impl Fn(isize) -> isize) -> Self {
+        Self::from_coords(self.coords.map(f))
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(isize, isize) -> isize) -> Self {
+        Self::from_coords(std::array::from_fn(|axis| {
+            f(self.coords[axis], other.coords[axis])
+        }))
+    }
+
+    pub fn signum(&self) -> Self {
+        self.map(isize::signum)
+    }
+
+    pub fn manhattan(&self) -> usize {
+        self.coords.iter().map(|coord| coord.unsigned_abs()).sum()
+    }
+
+    pub fn chebyshev(&self) -> usize {
+        self.coords
+            .iter()
+            .map(|coord| coord.unsigned_abs())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<const D: usize, T: Copy> VecN<D, T> {
+    pub fn piecewise_min(self, other: Self) -> Self {
+        self.zip_with(&other, isize::min)
+    }
+
+    pub fn piecewise_max(self, other: Self) -> Self {
+        self.zip_with(&other, isize::max)
+    }
+
+    /// The smallest box containing every point, as its minimum and maximum
+    /// corners (inclusive), or `None` if there are no points.
+    pub fn bounding_box(points: impl IntoIterator<Item = Self>) -> Option<(Self, Self)> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(bounds.map_or((point, point), |(min, max)| {
+                (point.piecewise_min(min), point.piecewise_max(max))
+            }))
+        })
+    }
+
+    /// Whether the vector is within the box between two corners (inclusive).
+    pub fn within(&self, min: Self, max: Self) -> bool {
+        (0..D).all(|axis| (min.coords[axis]..=max.coords[axis]).contains(&self.coords[axis]))
+    }
+
+    /// The unit vectors along each axis in both directions.
+    pub fn cardinals() -> impl Iterator<Item = Self> {
+        (0..D).flat_map(|axis| {
+            [1, -1].map(|sign| {
+                let mut coords = [0; D];
+                coords[axis] = sign;
+                Self::from_coords(coords)
+            })
+        })
+    }
+
+    /// Every vector with coordinates from -1 to 1, except zero.
+    pub fn units() -> impl Iterator<Item = Self> {
+        (0..3usize.pow(D as u32))
+            .map(|mut index| {
+                Self::from_coords(std::array::from_fn(|_| {
+                    let coord = (index % 3) as isize - 1;
+                    index /= 3;
+                    coord
+                }))
+            })
+            .filter(|unit| unit.coords != [0; D])
+    }
+
+    /// The vectors sharing a face with this one: 4 in 2D and 6 in 3D.
+    pub fn adjacents(self) -> impl Iterator<Item = Self> {
+        Self::cardinals().map(move |cardinal| self + cardinal)
+    }
+
+    /// The vectors sharing a face, edge or corner with this one: 8 in 2D and
+    /// 26 in 3D.
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        Self::units().map(move |unit| self + unit)
+    }
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self::from_coords([x, y])
+    }
+
+    pub const fn as_base_n(&self, base: isize) -> isize {
+        self.y() * base + self.x()
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: isize, y: isize, z: isize) -> Self {
+        Self::from_coords([x, y, z])
+    }
+
+    pub const fn z(&self) -> isize {
+        self.coords[2]
+    }
+}
+
+impl<const D: usize, T: Eq + Copy> VecN<D, T> {
+    pub fn range_inclusive(&self, other: Self) -> impl Iterator<Item = Self> {
+        let delta = (other - *self).signum();
+        let mut point = *self;
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            if point == other {
+                done = true;
+                Some(point)
+            } else {
+                let old_point = point;
+                point += delta;
+                Some(old_point)
+            }
+        })
+    }
+}
+
+impl<const D: usize, T> std::fmt::Debug for VecN<D, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Vec{D}(")?;
+        for (axis, coord) in self.coords.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{coord}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<const D: usize, T> Index<usize> for VecN<D, T> {
+    type Output = isize;
+
+    fn index(&self, axis: usize) -> &isize {
+        &self.coords[axis]
+    }
+}
+
+impl<const D: usize, T> IndexMut<usize> for VecN<D, T> {
+    fn index_mut(&mut self, axis: usize) -> &mut isize {
+        &mut self.coords[axis]
+    }
+}
+
+impl<const D: usize, T> Add for VecN<D, T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a + b)
+    }
+}
+
+impl<const D: usize, T> AddAssign for VecN<D, T> {
+    fn add_assign(&mut self, other: Self) {
+        for (coord, other) in self.coords.iter_mut().zip(other.coords) {
+            *coord += other;
+        }
+    }
+}
+
+impl<const D: usize, T> Sub for VecN<D, T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a - b)
+    }
+}
+
+impl<const D: usize, T> SubAssign for VecN<D, T> {
+    fn sub_assign(&mut self, other: Self) {
+        for (coord, other) in self.coords.iter_mut().zip(other.coords) {
+            *coord -= other;
+        }
+    }
+}
+
+/// Implement an operator between a vector and a scalar, applied to each coordinate.
+macro_rules! scalar_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        scalar_op!(@impl $trait, $method, $assign_trait, $assign_method, $op, isize);
+        scalar_op!(@impl $trait, $method, $assign_trait, $assign_method, $op, usize);
+    };
+    (@impl $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt, $scalar:ty) => {
+        impl<const D: usize, T> $trait<$scalar> for VecN<D, T> {
+            type Output = Self;
+
+            fn $method(self, other: $scalar) -> Self {
+                self.map(|coord| coord $op other as isize)
+            }
+        }
+
+        impl<const D: usize, T> $assign_trait<$scalar> for VecN<D, T> {
+            fn $assign_method(&mut self, other: $scalar) {
+                for coord in &mut self.coords {
+                    *coord = *coord $op other as isize;
+                }
+            }
+        }
+    };
+}
+
+scalar_op!(Mul, mul, MulAssign, mul_assign, *);
+scalar_op!(Div, div, DivAssign, div_assign, /);
+scalar_op!(Rem, rem, RemAssign, rem_assign, %);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distances() {
+        let v: Vec2 = Vec2::new(-3, 2);
+        assert_eq!((v.manhattan(), v.chebyshev()), (5, 3));
+        let v: Vec3 = Vec3::new(1, -4, 2);
+        assert_eq!((v.manhattan(), v.chebyshev()), (7, 4));
+        assert_eq!(Vec3::<()>::ZERO.chebyshev(), 0);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let points: [Vec2; 3] = [Vec2::new(2, -1), Vec2::new(-3, 4), Vec2::new(0, 0)];
+        assert_eq!(
+            Vec2::bounding_box(points),
+            Some((Vec2::new(-3, -1), Vec2::new(2, 4)))
+        );
+        let points: [Vec3; 2] = [Vec3::new(1, 5, -2), Vec3::new(3, 0, 7)];
+        assert_eq!(
+            Vec3::bounding_box(points),
+            Some((Vec3::new(1, 0, -2), Vec3::new(3, 5, 7)))
+        );
+        assert_eq!(Vec2::<()>::bounding_box([]), None);
+        assert_eq!(Vec3::<()>::bounding_box([]), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let v: Vec2 = Vec2::new(5, 5);
+        assert_eq!(Vec2::<()>::units().count(), 8);
+        assert_eq!(v.adjacents().count(), 4);
+        assert_eq!(v.neighbours().count(), 8);
+        assert!(v.adjacents().all(|adj| (adj - v).manhattan() == 1));
+        assert!(v.neighbours().all(|adj| (adj - v).chebyshev() == 1));
+
+        let v: Vec3 = Vec3::new(1, 2, 3);
+        assert_eq!(Vec3::<()>::units().count(), 26);
+        assert_eq!(v.adjacents().count(), 6);
+        assert_eq!(v.neighbours().count(), 26);
+        assert!(v.adjacents().all(|adj| (adj - v).manhattan() == 1));
+        assert!(v.neighbours().all(|adj| (adj - v).chebyshev() == 1));
+        assert!(!v.neighbours().any(|adj| adj == v));
+    }
+}
@@ -0,0 +1,288 @@
+//! Transforms from vectors in one coordinate space to another. Each transform
+//! names the spaces it maps between, so a vector can't be used in the wrong
+//! frame without going through the transform.
+//!
+//! Days can tie a transform to a pair of spaces by implementing `From`, for
+//! example `impl From<Vec2<Local>> for Vec2<Global>`.
+
+use std::marker::PhantomData;
+
+use super::Vec2;
+
+/// Maps vectors in space `A` to vectors in space `B`.
+pub trait Transform<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B>;
+
+    /// Transform a direction or displacement rather than a position. These
+    /// are unaffected by translation.
+    fn apply_direction(&self, v: Vec2<A>) -> Vec2<B> {
+        self.apply(v)
+    }
+
+    /// Apply this transform and then another.
+    #[allow(dead_code)]
+    fn then<C, T: Transform<B, C>>(self, next: T) -> Then<Self, T, B>
+    where
+        Self: Sized,
+    {
+        Then {
+            first: self,
+            second: next,
+            via: PhantomData,
+        }
+    }
+}
+
+/// A transform which can be undone exactly.
+pub trait Invert<A, B>: Transform<A, B> {
+    type Inverse: Transform<B, A>;
+
+    fn inverse(&self) -> Self::Inverse;
+}
+
+/// Two transforms applied one after the other, going through space `B`.
+#[allow(dead_code)]
+pub struct Then<T, U, B> {
+    first: T,
+    second: U,
+    via: PhantomData<fn() -> B>,
+}
+
+impl<A, B, C, T: Transform<A, B>, U: Transform<B, C>> Transform<A, C> for Then<T, U, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<C> {
+        self.second.apply(self.first.apply(v))
+    }
+
+    fn apply_direction(&self, v: Vec2<A>) -> Vec2<C> {
+        self.second.apply_direction(self.first.apply_direction(v))
+    }
+}
+
+impl<A, B, C, T: Invert<A, B>, U: Invert<B, C>> Invert<A, C> for Then<T, U, B> {
+    type Inverse = Then<U::Inverse, T::Inverse, B>;
+
+    fn inverse(&self) -> Self::Inverse {
+        self.second.inverse().then(self.first.inverse())
+    }
+}
+
+/// Moves vectors by a fixed offset.
+pub struct Translate<A, B> {
+    offset: Vec2,
+    spaces: PhantomData<fn(A) -> B>,
+}
+
+impl<A, B> Translate<A, B> {
+    pub const fn new(offset: Vec2) -> Self {
+        Self {
+            offset,
+            spaces: PhantomData,
+        }
+    }
+}
+
+impl<A, B> Transform<A, B> for Translate<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B> {
+        Vec2::new(v.x() + self.offset.x(), v.y() + self.offset.y())
+    }
+
+    fn apply_direction(&self, v: Vec2<A>) -> Vec2<B> {
+        Vec2::from_coords(v.coords())
+    }
+}
+
+impl<A, B> Invert<A, B> for Translate<A, B> {
+    type Inverse = Translate<B, A>;
+
+    fn inverse(&self) -> Self::Inverse {
+        Translate::new(Vec2::ZERO - self.offset)
+    }
+}
+
+/// Multiplies vectors by a fixed factor. This can't generally be undone with
+/// integer coordinates, so it has no inverse.
+#[allow(dead_code)]
+pub struct Scale<A, B> {
+    factor: isize,
+    spaces: PhantomData<fn(A) -> B>,
+}
+
+impl<A, B> Scale<A, B> {
+    #[allow(dead_code)]
+    pub const fn new(factor: isize) -> Self {
+        Self {
+            factor,
+            spaces: PhantomData,
+        }
+    }
+}
+
+impl<A, B> Transform<A, B> for Scale<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B> {
+        Vec2::new(v.x() * self.factor, v.y() * self.factor)
+    }
+}
+
+/// Rotates vectors about the origin by a number of quarter turns, clockwise
+/// when y points down.
+pub struct Rotate90<A, B> {
+    quarter_turns: u8,
+    spaces: PhantomData<fn(A) -> B>,
+}
+
+impl<A, B> Rotate90<A, B> {
+    pub const fn new(quarter_turns: u8) -> Self {
+        Self {
+            quarter_turns: quarter_turns % 4,
+            spaces: PhantomData,
+        }
+    }
+
+    pub const fn clockwise() -> Self {
+        Self::new(1)
+    }
+
+    pub const fn anticlockwise() -> Self {
+        Self::new(3)
+    }
+}
+
+impl<A, B> Transform<A, B> for Rotate90<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B> {
+        let [x, y] = v.coords();
+        match self.quarter_turns {
+            0 => Vec2::new(x, y),
+            1 => Vec2::new(-y, x),
+            2 => Vec2::new(-x, -y),
+            _ => Vec2::new(y, -x),
+        }
+    }
+}
+
+impl<A, B> Invert<A, B> for Rotate90<A, B> {
+    type Inverse = Rotate90<B, A>;
+
+    fn inverse(&self) -> Self::Inverse {
+        Rotate90::new(4 - self.quarter_turns)
+    }
+}
+
+/// Mirrors vectors by negating one axis (0 for x, 1 for y).
+#[allow(dead_code)]
+pub struct Reflect<A, B> {
+    axis: usize,
+    spaces: PhantomData<fn(A) -> B>,
+}
+
+impl<A, B> Reflect<A, B> {
+    #[allow(dead_code)]
+    pub const fn new(axis: usize) -> Self {
+        Self {
+            axis,
+            spaces: PhantomData,
+        }
+    }
+}
+
+impl<A, B> Transform<A, B> for Reflect<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B> {
+        let mut coords = v.coords();
+        coords[self.axis] *= -1;
+        Vec2::from_coords(coords)
+    }
+}
+
+impl<A, B> Invert<A, B> for Reflect<A, B> {
+    type Inverse = Reflect<B, A>;
+
+    fn inverse(&self) -> Self::Inverse {
+        Reflect::new(self.axis)
+    }
+}
+
+/// Rotates vectors by 45° clockwise (when y points down), scaling them up
+/// by √2 to keep integer coordinates: `(x, y)` becomes `(x - y, x + y)`.
+///
+/// Manhattan distance in the original space becomes Chebyshev distance in the
+/// rotated space, so diamonds become axis-aligned squares. Only vectors whose
+/// coordinates have the same parity map back to integer coordinates.
+pub struct Rotate45<A, B> {
+    inverted: bool,
+    spaces: PhantomData<fn(A) -> B>,
+}
+
+impl<A, B> Rotate45<A, B> {
+    pub const fn new() -> Self {
+        Self {
+            inverted: false,
+            spaces: PhantomData,
+        }
+    }
+}
+
+impl<A, B> Transform<A, B> for Rotate45<A, B> {
+    fn apply(&self, v: Vec2<A>) -> Vec2<B> {
+        let [x, y] = v.coords();
+        if self.inverted {
+            let y = (y - x) / 2;
+            Vec2::new(x + y, y)
+        } else {
+            Vec2::new(x - y, x + y)
+        }
+    }
+}
+
+impl<A, B> Invert<A, B> for Rotate45<A, B> {
+    type Inverse = Rotate45<B, A>;
+
+    fn inverse(&self) -> Self::Inverse {
+        Rotate45 {
+            inverted: !self.inverted,
+            spaces: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy)]
+    struct A;
+    #[derive(Clone, Copy)]
+    struct B;
+    #[derive(Clone, Copy)]
+    struct C;
+
+    fn round_trip<T: Invert<A, B>>(transform: &T, v: Vec2<A>) -> Vec2<A> {
+        transform.inverse().apply(transform.apply(v))
+    }
+
+    #[test]
+    fn test_rotations() {
+        let v = Vec2::<A>::new(3, 1);
+        assert_eq!(Rotate90::<A, B>::clockwise().apply(v).coords(), [-1, 3]);
+        assert_eq!(Rotate90::<A, B>::anticlockwise().apply(v).coords(), [1, -3]);
+        assert_eq!(Rotate45::<A, B>::new().apply(v).coords(), [2, 4]);
+        for quarter_turns in 0..4 {
+            assert_eq!(
+                round_trip(&Rotate90::new(quarter_turns), v).coords(),
+                [3, 1]
+            );
+        }
+        assert_eq!(round_trip(&Rotate45::new(), v).coords(), [3, 1]);
+    }
+
+    #[test]
+    fn test_composition() {
+        let v = Vec2::<A>::new(3, 1);
+        let transform = Translate::<A, B>::new(Vec2::new(10, 20)).then(Reflect::<B, C>::new(1));
+        assert_eq!(transform.apply(v).coords(), [13, -21]);
+        assert_eq!(transform.apply_direction(v).coords(), [3, -1]);
+        assert_eq!(
+            transform.inverse().apply(transform.apply(v)).coords(),
+            [3, 1]
+        );
+        assert_eq!(Scale::<A, B>::new(3).apply(v).coords(), [9, 3]);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use gif::{Encoder, Repeat};
+use rustc_hash::FxHashMap;
+
+use super::Frame;
+use crate::vec2::Vec2;
+
+/// The largest width or height to scale frames up to.
+const MAX_SIZE: usize = 800;
+const BACKGROUND: [u8; 3] = [15, 15, 35];
+
+/// The colour to draw a character as.
+const fn colour(c: char) -> [u8; 3] {
+    match c {
+        ' ' | '.' => BACKGROUND,
+        '#' => [140, 140, 150],
+        '|' | '-' | '+' => [80, 80, 90],
+        'o' | '~' => [230, 190, 80],
+        '@' | 'E' | 'H' => [255, 255, 255],
+        '^' | 'v' | '<' | '>' | '*' => [110, 170, 255],
+        '0'..='9' => [255, 110, 110],
+        _ => {
+            // Anything else gets an arbitrary but consistent bright colour.
+            let hash = (c as u32).wrapping_mul(2_654_435_761);
+            [
+                128 | (hash >> 24) as u8,
+                128 | (hash >> 16) as u8,
+                128 | (hash >> 8) as u8,
+            ]
+        }
+    }
+}
+
+/// Write frames as an animated GIF. Each character becomes a square of
+/// pixels, and frames are placed by their origin on a canvas big enough for
+/// all of them.
+pub fn write(frames: &[Frame], delay: Duration, path: &Path) -> io::Result<()> {
+    let corners = frames.iter().flat_map(|frame| {
+        let size = Vec2::new(frame.grid.width() as isize, frame.grid.height() as isize);
+        [frame.origin, frame.origin + size]
+    });
+    let Some((min, max)) = Vec2::bounding_box(corners) else {
+        return Ok(());
+    };
+    let (width, height) = ((max.x() - min.x()) as usize, (max.y() - min.y()) as usize);
+    let scale = (MAX_SIZE / width.max(height)).max(1);
+
+    let mut palette = vec![BACKGROUND];
+    let mut indices = FxHashMap::default();
+    let mut index_of = |c: char| -> u8 {
+        *indices.entry(colour(c)).or_insert_with(|| {
+            palette.push(colour(c));
+            // Colours past the palette's limit reuse the last one.
+            (palette.len() - 1).min(255) as u8
+        })
+    };
+    let pixel_frames: Vec<Vec<u8>> = frames
+        .iter()
+        .map(|frame| {
+            let offset = frame.origin - min;
+            let mut pixels = vec![0; width * height * scale * scale];
+            for (pos, &c) in frame.grid.iter() {
+                let index = index_of(c);
+                let (x, y) = ((pos + offset).x() as usize, (pos + offset).y() as usize);
+                for row in y * scale..(y + 1) * scale {
+                    let start = row * width * scale + x * scale;
+                    pixels[start..start + scale].fill(index);
+                }
+            }
+            pixels
+        })
+        .collect();
+    palette.truncate(256);
+
+    let to_io = io::Error::other;
+    let file = io::BufWriter::new(fs::File::create(path)?);
+    let (width, height) = ((width * scale) as u16, (height * scale) as u16);
+    let mut encoder = Encoder::new(file, width, height, &palette.concat()).map_err(to_io)?;
+    encoder.set_repeat(Repeat::Infinite).map_err(to_io)?;
+    for pixels in pixel_frames {
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+        frame.delay = (delay.as_millis() / 10) as u16;
+        encoder.write_frame(&frame).map_err(to_io)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn test_round_trip() {
+        let frames = [
+            Frame {
+                origin: Vec2::new(0, 0),
+                grid: Grid::parse("#.\n.#\n", |c| c),
+            },
+            Frame {
+                origin: Vec2::new(-1, 1),
+                grid: Grid::parse("o\n", |c| c),
+            },
+        ];
+        let path = std::env::temp_dir().join("aoc22_round_trip.gif");
+        write(&frames, Duration::from_millis(100), &path).unwrap();
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(fs::File::open(&path).unwrap())
+            .unwrap();
+        let scale = MAX_SIZE / 3;
+        assert_eq!(
+            (decoder.width(), decoder.height()),
+            (3 * scale as u16, 2 * scale as u16)
+        );
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 10);
+            count += 1;
+        }
+        assert_eq!(count, 2);
+        fs::remove_file(path).unwrap();
+    }
+}
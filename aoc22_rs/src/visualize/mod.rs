@@ -0,0 +1,153 @@
+mod gif;
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crossterm::{cursor, execute, style::Print, terminal};
+use log::warn;
+
+use crate::{cli::DayPick, data, grid::Grid, vec2::Vec2, Day};
+
+/// Implemented by days which can show their simulation frame by frame.
+pub trait Visualize: Day {
+    /// Run the simulation, recording frames along the way.
+    fn visualize(&self, recorder: &mut Recorder);
+}
+
+/// One picture of a simulation, as a grid of characters. `origin` is the
+/// position of the grid's top left corner, so frames which cover different
+/// areas line up when saved as an animation.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    pub origin: Vec2,
+    pub grid: Grid<char>,
+}
+
+/// Where recorded frames go.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Every frame one after the other in a text file.
+    Text,
+    /// An animated GIF.
+    Gif,
+}
+
+pub struct Options {
+    /// The directory to save frames to, or `None` to animate them in the
+    /// terminal.
+    pub dir: Option<PathBuf>,
+    pub format: Format,
+    pub delay: Duration,
+}
+
+/// Collects frames from a [`Visualize`] day and shows or saves them.
+pub struct Recorder<'a> {
+    options: &'a Options,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Recorder<'a> {
+    const fn new(options: &'a Options) -> Self {
+        Self {
+            options,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, grid: Grid<char>) {
+        self.record_at(Vec2::ZERO, grid);
+    }
+
+    pub fn record_at(&mut self, origin: Vec2, grid: Grid<char>) {
+        let frame = Frame { origin, grid };
+        if self.options.dir.is_some() {
+            self.frames.push(frame);
+        } else {
+            show(&frame).unwrap();
+            std::thread::sleep(self.options.delay);
+        }
+    }
+
+    fn finish(self, day: usize) -> io::Result<()> {
+        let Some(dir) = &self.options.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)?;
+        match self.options.format {
+            Format::Text => write_text(&self.frames, &dir.join(format!("day{day:02}.txt"))),
+            Format::Gif => gif::write(
+                &self.frames,
+                self.options.delay,
+                &dir.join(format!("day{day:02}.gif")),
+            ),
+        }
+    }
+}
+
+/// Draw a frame over the whole terminal, cropped to fit.
+fn show(frame: &Frame) -> io::Result<()> {
+    let (columns, rows) = terminal::size().unwrap_or((80, 24));
+    let text: Vec<String> = frame
+        .grid
+        .rows()
+        .take(rows as usize - 1)
+        .map(|row| row.iter().take(columns as usize).collect())
+        .collect();
+    execute!(
+        io::stdout(),
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0),
+        Print(text.join("\r\n")),
+    )?;
+    io::stdout().flush()
+}
+
+fn write_text(frames: &[Frame], path: &Path) -> io::Result<()> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for (index, frame) in frames.iter().enumerate() {
+        writeln!(file, "Frame {index} at {:?}", frame.origin)?;
+        writeln!(file, "{}", frame.grid)?;
+    }
+    file.flush()
+}
+
+macro_rules! visualize_days {
+    ( $pick:expr, $data:expr, $options:expr, { $( $num:literal => $day_mod:ident::$day_type:ident, )* } ) => {
+        match $pick.day {
+            $( $num => visualize_day::<crate::days::$day_mod::$day_type>($pick.day, $data, $options), )*
+            day => {
+                warn!("Day {day} has no visualisation");
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Visualize each picked day which supports it.
+pub fn run(data: &data::All, picks: &[DayPick; 25], options: &Options) -> io::Result<()> {
+    for pick in picks {
+        if !(pick.parse || pick.part1 || pick.part2) {
+            continue;
+        }
+        visualize_days!(pick, data, options, {
+            9 => day09::Day9,
+            14 => day14::Day14,
+            17 => day17::Day17,
+            22 => day22::Day22,
+            23 => day23::Day23,
+            24 => day24::Day24,
+        })?;
+    }
+    Ok(())
+}
+
+fn visualize_day<D: Visualize>(day: usize, data: &data::All, options: &Options) -> io::Result<()> {
+    let solution = D::parse(data[day - 1].input);
+    let mut recorder = Recorder::new(options);
+    solution.visualize(&mut recorder);
+    recorder.finish(day)
+}
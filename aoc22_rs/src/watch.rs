@@ -0,0 +1,232 @@
+use crate::{
+    cli::DayPick,
+    output::{self, Style, TableOptions},
+    runner::{DayTimings, VARIANTS},
+};
+use log::{debug, error};
+use rustc_hash::FxHashMap;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const WATCHED_DIR: &str = "src";
+
+/// Re-run the picked days, with any chosen variants, whenever their solution,
+/// their input or any code they share changes, forever.
+pub fn run(picks: &[DayPick; 25], variants: &[String], table: &TableOptions, style: Style) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut snapshot = Snapshot::take(root);
+    let mut previous: FxHashMap<u32, DayTimings> = FxHashMap::default();
+    let mut to_run: Vec<usize> = (1..=25).collect();
+    loop {
+        let picked: Vec<DayPick> = to_run
+            .iter()
+            .map(|&day| picks[day - 1])
+            .filter(|pick| pick.parse || pick.part1 || pick.part2)
+            .collect();
+        if !picked.is_empty() {
+            if let Some(timings) = run_days(root, &picked, variants) {
+                output::print_timings(&timings, table, style);
+                for timing in timings {
+                    if let Some(old) = previous.get(&timing.day) {
+                        print_deltas(old, &timing);
+                    }
+                    previous.insert(timing.day, timing);
+                }
+            }
+            println!("Watching for changes...");
+        }
+        let changed = loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let new_snapshot = Snapshot::take(root);
+            let changed = snapshot.changed(&new_snapshot);
+            snapshot = new_snapshot;
+            if !changed.is_empty() {
+                break changed;
+            }
+        };
+        to_run = affected_days(&changed);
+        debug!("Changed: {changed:?}, re-running days {to_run:?}");
+    }
+}
+
+/// Modification times of every file in the watched directory, apart from
+/// hidden and temporary files.
+struct Snapshot(FxHashMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+    fn take(root: &Path) -> Self {
+        let mut files = FxHashMap::default();
+        let mut dirs = vec![root.join(WATCHED_DIR)];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if is_temporary(&entry.file_name().to_string_lossy()) {
+                    continue;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                } else if let Ok(modified) = metadata.modified() {
+                    files.insert(entry.path(), modified);
+                }
+            }
+        }
+        Self(files)
+    }
+
+    /// Files which were added, removed or modified between two snapshots.
+    fn changed(&self, newer: &Self) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = newer
+            .0
+            .iter()
+            .filter(|(path, modified)| self.0.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(
+            self.0
+                .keys()
+                .filter(|path| !newer.0.contains_key(*path))
+                .cloned(),
+        );
+        changed
+    }
+}
+
+/// Whether a file is hidden or left by an editor while saving, like
+/// `.day17.rs.swp`, `day17.rs~` or vim's `4913`, so changes to it don't matter.
+fn is_temporary(name: &str) -> bool {
+    name.starts_with('.')
+        || name.ends_with('~')
+        || (name.starts_with('#') && name.ends_with('#'))
+        || name.bytes().all(|byte| byte.is_ascii_digit())
+        || [".swp", ".swx", ".tmp"]
+            .iter()
+            .any(|extension| name.ends_with(extension))
+}
+
+/// Work out which days a set of changed files could affect.
+///
+/// Solutions (including alternative versions such as `day11a.rs` and modules
+/// such as `day22/`) and inputs are named after their day. Anything else, like
+/// `days/mod.rs` or a shared module like `grid.rs`, could affect any day.
+fn affected_days(changed: &[PathBuf]) -> Vec<usize> {
+    let mut days = Vec::new();
+    for path in changed {
+        let day = path
+            .components()
+            .filter_map(|component| day_number(component.as_os_str().to_str()?))
+            .next_back();
+        match day {
+            Some(day) => days.push(day),
+            None => return (1..=25).collect(),
+        }
+    }
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+/// Parse the day number from a name like `day05.txt`, `day_16a.rs` or `day22`.
+fn day_number(name: &str) -> Option<usize> {
+    let digits: String = name
+        .strip_prefix("day")?
+        .trim_start_matches('_')
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok().filter(|day| (1..=25).contains(day))
+}
+
+/// Rebuild and run the given days in release mode, returning their timings,
+/// or `None` if the build or run failed. Only the variants for those days are
+/// passed on, as the others would be rejected.
+fn run_days(root: &Path, picks: &[DayPick], variants: &[String]) -> Option<Vec<DayTimings>> {
+    let parts = picks.iter().flat_map(|pick| {
+        [(pick.parse, 'p'), (pick.part1, 'a'), (pick.part2, 'b')]
+            .into_iter()
+            .filter(|(picked, _)| *picked)
+            .map(move |(_, suffix)| format!("{}{suffix}", pick.day))
+    });
+    let variants = VARIANTS
+        .into_iter()
+        .filter(|&(name, day)| {
+            variants.iter().any(|chosen| chosen == name) && picks.iter().any(|pick| pick.day == day)
+        })
+        .flat_map(|(name, _)| ["--variant", name]);
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(cargo)
+        .args(["run", "--release", "--quiet", "--", "--json"])
+        .args(variants)
+        .args(parts)
+        .current_dir(root)
+        .output()
+        .map_err(|err| error!("Could not run cargo: {err}"))
+        .ok()?;
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() {
+        error!("Build or run failed ({})", output.status);
+        return None;
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|err| error!("Could not read timings: {err}"))
+        .ok()
+}
+
+fn print_deltas(old: &DayTimings, new: &DayTimings) {
+    let parts = [
+        ("parse", old.parse, new.parse),
+        (
+            "part 1",
+            old.part1.clone().and_then(Result::ok),
+            new.part1.clone().and_then(Result::ok),
+        ),
+        (
+            "part 2",
+            old.part2.clone().and_then(Result::ok),
+            new.part2.clone().and_then(Result::ok),
+        ),
+    ];
+    for (name, old_timing, new_timing) in parts {
+        let (Some(old_timing), Some(new_timing)) = (old_timing, new_timing) else {
+            continue;
+        };
+        let change = (new_timing.as_secs_f64() / old_timing.as_secs_f64() - 1.0) * 100.0;
+        println!(
+            "Day {} {name}: {old_timing:?} -> {new_timing:?} ({change:+.1}%)",
+            new.day
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affected_days() {
+        let paths = |names: &[&str]| -> Vec<PathBuf> { names.iter().map(PathBuf::from).collect() };
+        assert_eq!(
+            affected_days(&paths(&["src/days/day22/part2.rs", "src/inputs/day05.txt"])),
+            [5, 22]
+        );
+        assert_eq!(affected_days(&paths(&["src/grid.rs"])).len(), 25);
+        for name in [
+            ".day17.rs.swp",
+            "day17.rs~",
+            "#day17.rs#",
+            "4913",
+            "day17.rs.tmp",
+        ] {
+            assert!(is_temporary(name), "{name}");
+        }
+        assert!(!is_temporary("day17.rs"));
+    }
+}